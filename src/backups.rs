@@ -0,0 +1,133 @@
+use crate::{
+    import_export::show_error,
+    persistence::{list_backups, read_backup},
+    stock_manager::StockManager,
+};
+use gtk::{
+    Align, Application, ApplicationWindow, Box, Label, ListBox, ListBoxRow, Orientation,
+    ScrolledWindow, SelectionMode, Window,
+    gio::SimpleAction,
+    glib::{self, DateTime},
+    prelude::*,
+};
+use std::{path::PathBuf, rc::Rc, time::UNIX_EPOCH};
+
+/// Registers the `app.restore-backup` action
+pub fn add_actions(
+    application: &Application,
+    window: &ApplicationWindow,
+    manager: Rc<StockManager>,
+) {
+    let restore_action = SimpleAction::new("restore-backup", None);
+    restore_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        #[strong]
+        manager,
+        move |_, _| show_backup_window(&window, manager.clone())
+    ));
+    application.add_action(&restore_action);
+}
+
+/// Lists saved backups, activating one replaces the watchlist with it. The current
+/// watchlist is itself backed up first, so restoring can be undone the same way.
+fn show_backup_window(parent: &ApplicationWindow, manager: Rc<StockManager>) {
+    let backups = list_backups();
+
+    let list = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+
+    for (path, modified) in &backups {
+        let timestamp = modified
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(|d| DateTime::from_unix_local(d.as_secs() as i64).ok())
+            .and_then(|dt| dt.format("%Y-%m-%d %H:%M:%S").ok())
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+        let count = read_backup(path)
+            .map(|entries| format!("{} tickers", entries.len()))
+            .unwrap_or_else(|_| "Unreadable".to_string());
+
+        let row_box = Box::new(Orientation::Horizontal, 12);
+        row_box.append(
+            &Label::builder()
+                .label(&timestamp)
+                .hexpand(true)
+                .xalign(0.0)
+                .build(),
+        );
+        row_box.append(
+            &Label::builder()
+                .label(&count)
+                .css_classes(["dim-label"])
+                .build(),
+        );
+
+        let row = ListBoxRow::builder().child(&row_box).build();
+        list.append(&row);
+    }
+
+    let layout = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let hint = if backups.is_empty() {
+        "No backups yet, one is made every time the watchlist is saved."
+    } else {
+        "Activate a backup to restore it."
+    };
+    layout.append(
+        &Label::builder()
+            .label(hint)
+            .halign(Align::Start)
+            .wrap(true)
+            .build(),
+    );
+    layout.append(
+        &ScrolledWindow::builder()
+            .child(&list)
+            .vexpand(true)
+            .min_content_height(160)
+            .build(),
+    );
+
+    let backup_window = Window::builder()
+        .title("Restore Backup")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(360)
+        .default_height(300)
+        .child(&layout)
+        .build();
+
+    let paths: Vec<PathBuf> = backups.into_iter().map(|(path, _)| path).collect();
+    list.connect_row_activated(glib::clone!(
+        #[weak]
+        backup_window,
+        move |_, row| {
+            let Some(path) = paths.get(row.index() as usize) else {
+                return;
+            };
+            match read_backup(path) {
+                Ok(entries) => {
+                    manager.replace_entries(&entries);
+                    manager
+                        .toast()
+                        .show(&format!("Restored {} tickers from backup", entries.len()));
+                    backup_window.close();
+                }
+                Err(e) => show_error(&backup_window, "Restore failed", &e.to_string()),
+            }
+        }
+    ));
+
+    backup_window.present();
+}
@@ -0,0 +1,505 @@
+use crate::{
+    csv_io::CsvImport,
+    persistence::{ImportEntry, get_config_dir, write_atomic},
+    stock_api::StockApi,
+};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs::read_to_string, path::PathBuf};
+
+/// Describes which columns of a broker CSV hold the fields we need
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MappingProfile {
+    pub name: String,
+    pub symbol_column: String,
+    pub quantity_column: String,
+    pub price_column: String,
+    /// Security name, used to search for a ticker when the symbol is unknown to Yahoo
+    #[serde(default)]
+    pub name_column: Option<String>,
+    /// Column holding the transaction type, without it the sign of the quantity decides
+    #[serde(default)]
+    pub action_column: Option<String>,
+    #[serde(default)]
+    pub buy_values: Vec<String>,
+    #[serde(default)]
+    pub sell_values: Vec<String>,
+}
+
+impl MappingProfile {
+    /// True if every mapped column exists in `headers`
+    pub fn matches(&self, headers: &[String]) -> bool {
+        let has = |col: &str| headers.iter().any(|h| h.eq_ignore_ascii_case(col));
+        has(&self.symbol_column)
+            && has(&self.quantity_column)
+            && has(&self.price_column)
+            && self.name_column.as_deref().is_none_or(has)
+            && self.action_column.as_deref().is_none_or(has)
+    }
+}
+
+/// A single buy or sell, sells have a negative quantity
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub symbol: String,
+    pub name: String,
+    pub quantity: f64,
+    pub price: f64,
+}
+
+/// OFX transaction aggregates that change the number of units held
+const OFX_TRADES: [&str; 10] = [
+    "BUYDEBT",
+    "BUYMF",
+    "BUYOPT",
+    "BUYOTHER",
+    "BUYSTOCK",
+    "SELLDEBT",
+    "SELLMF",
+    "SELLOPT",
+    "SELLOTHER",
+    "SELLSTOCK",
+];
+
+fn profiles_path() -> PathBuf {
+    get_config_dir().join("broker_profiles.json")
+}
+
+pub fn load_profiles() -> Vec<MappingProfile> {
+    read_to_string(profiles_path())
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Saves `profile`, replacing any existing profile with the same name
+pub fn save_profile(profile: MappingProfile) {
+    let mut profiles = load_profiles();
+    profiles.retain(|p| p.name != profile.name);
+    profiles.push(profile);
+
+    if let Ok(json) = serde_json::to_string_pretty(&profiles)
+        && let Err(e) = write_atomic(&profiles_path(), json.as_bytes())
+    {
+        eprintln!("Failed to save broker profiles: {}", e);
+    }
+}
+
+/// Returns the header row of a CSV file, with the delimiter it uses
+pub fn read_headers(data: &str) -> Result<(Vec<String>, u8)> {
+    let delimiter = detect_delimiter(data);
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(csv::Trim::All)
+        .from_reader(data.as_bytes());
+
+    let headers = reader.headers()?.iter().map(String::from).collect();
+    Ok((headers, delimiter))
+}
+
+/// Extracts trades from a broker CSV using `profile`, rows that fail to parse are reported
+pub fn parse_mapped_csv(data: &str, profile: &MappingProfile) -> Result<(Vec<Trade>, Vec<String>)> {
+    let (headers, delimiter) = read_headers(data)?;
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let required = |name: &str| column(name).ok_or_else(|| anyhow!("Missing column \"{}\"", name));
+
+    let symbol_col = required(&profile.symbol_column)?;
+    let quantity_col = required(&profile.quantity_column)?;
+    let price_col = required(&profile.price_column)?;
+    let name_col = profile.name_column.as_deref().and_then(column);
+    let action_col = profile.action_column.as_deref().and_then(column);
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(data.as_bytes());
+
+    let records: Vec<_> = reader.records().collect();
+    let decimal = detect_decimal_separator(records.iter().flatten().flat_map(|record| {
+        [quantity_col, price_col]
+            .into_iter()
+            .filter_map(|col| record.get(col))
+    }));
+
+    let mut trades = vec![];
+    let mut errors = vec![];
+
+    for (i, record) in records.into_iter().enumerate() {
+        let line = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(format!("Line {}: {}", line, e));
+                continue;
+            }
+        };
+        let field = |col: Option<usize>| col.and_then(|c| record.get(c)).unwrap_or("");
+
+        let sign = match action_col {
+            Some(col) => {
+                let action = field(Some(col));
+                let is = |values: &[String]| values.iter().any(|v| v.eq_ignore_ascii_case(action));
+                if is(&profile.sell_values) {
+                    -1.0
+                } else if profile.buy_values.is_empty() || is(&profile.buy_values) {
+                    1.0
+                } else {
+                    // Dividends, fees, transfers and so on
+                    continue;
+                }
+            }
+            None => 1.0,
+        };
+
+        let symbol = field(Some(symbol_col)).to_string();
+        let name = field(name_col).to_string();
+        if symbol.is_empty() && name.is_empty() {
+            continue;
+        }
+
+        let amounts = parse_amount(field(Some(quantity_col)), decimal)
+            .and_then(|quantity| Ok((quantity, parse_amount(field(Some(price_col)), decimal)?)));
+        let (quantity, price) = match amounts {
+            Ok(amounts) => amounts,
+            Err(e) => {
+                errors.push(format!("Line {}: {}", line, e));
+                continue;
+            }
+        };
+
+        // Brokers disagree on whether sells are negative, the action column wins if present
+        let quantity = if action_col.is_some() {
+            quantity.abs() * sign
+        } else {
+            quantity
+        };
+
+        trades.push(Trade {
+            symbol,
+            name,
+            quantity,
+            price: price.abs(),
+        });
+    }
+
+    Ok((trades, errors))
+}
+
+/// Extracts buy and sell transactions from an OFX/QFX investment statement.
+/// Handles both the SGML (1.x) and XML (2.x) flavours.
+pub fn parse_ofx(data: &str) -> Result<Vec<Trade>> {
+    if !data.contains("<OFX>") {
+        return Err(anyhow!("Not an OFX file"));
+    }
+
+    let mut securities: HashMap<String, (String, String)> = HashMap::new();
+    let mut transactions: Vec<(String, f64, f64, bool)> = vec![];
+
+    let mut in_secinfo = false;
+    let mut in_trade: Option<bool> = None;
+    let mut unique_id = String::new();
+    let mut ticker = String::new();
+    let mut sec_name = String::new();
+    let mut units = 0.0;
+    let mut unit_price = 0.0;
+
+    for token in data.split('<').skip(1) {
+        let (tag, value) = token.split_once('>').unwrap_or((token, ""));
+        let value = value.trim();
+
+        match tag {
+            "SECINFO" => {
+                in_secinfo = true;
+                unique_id.clear();
+                ticker.clear();
+                sec_name.clear();
+            }
+            "/SECINFO" => {
+                in_secinfo = false;
+                securities.insert(unique_id.clone(), (ticker.clone(), sec_name.clone()));
+            }
+            t if OFX_TRADES.contains(&t) => {
+                in_trade = Some(t.starts_with("SELL"));
+                unique_id.clear();
+                units = 0.0;
+                unit_price = 0.0;
+            }
+            t if t.strip_prefix('/').is_some_and(|t| OFX_TRADES.contains(&t)) => {
+                if let Some(is_sell) = in_trade.take() {
+                    transactions.push((unique_id.clone(), units, unit_price, is_sell));
+                }
+            }
+            "UNIQUEID" if in_secinfo || in_trade.is_some() => unique_id = value.to_string(),
+            "TICKER" if in_secinfo => ticker = value.to_string(),
+            "SECNAME" if in_secinfo => sec_name = value.to_string(),
+            // OFX always uses a decimal point
+            "UNITS" if in_trade.is_some() => units = parse_amount(value, Some('.')).unwrap_or(0.0),
+            "UNITPRICE" if in_trade.is_some() => {
+                unit_price = parse_amount(value, Some('.')).unwrap_or(0.0)
+            }
+            _ => {}
+        }
+    }
+
+    Ok(transactions
+        .into_iter()
+        .map(|(id, units, price, is_sell)| {
+            let (symbol, name) = securities.get(&id).cloned().unwrap_or_default();
+            Trade {
+                // Without a SECLIST entry the CUSIP is all we have, the lookup may still find it
+                symbol: if symbol.is_empty() { id } else { symbol },
+                name,
+                quantity: if is_sell { -units.abs() } else { units.abs() },
+                price,
+            }
+        })
+        .collect())
+}
+
+/// Folds trades into one holding per symbol, using the average cost method
+pub fn trades_to_holdings(trades: &[Trade]) -> Vec<Trade> {
+    let mut holdings: Vec<Trade> = vec![];
+
+    for trade in trades {
+        let holding = match holdings.iter_mut().find(|h| h.symbol == trade.symbol) {
+            Some(holding) => holding,
+            None => {
+                holdings.push(Trade {
+                    quantity: 0.0,
+                    price: 0.0,
+                    ..trade.clone()
+                });
+                holdings.last_mut().unwrap()
+            }
+        };
+
+        if trade.quantity >= 0.0 {
+            let total_cost = holding.quantity * holding.price + trade.quantity * trade.price;
+            holding.quantity += trade.quantity;
+            if holding.quantity > 0.0 {
+                holding.price = total_cost / holding.quantity;
+            }
+        } else {
+            holding.quantity = (holding.quantity + trade.quantity).max(0.0);
+        }
+    }
+
+    holdings.retain(|h| h.quantity > 0.0);
+    holdings
+}
+
+/// Maps each holding to a Yahoo ticker. The symbol has to match a ticker exactly, failing
+/// that the name is searched for, which only counts when it leads to a single listing.
+/// Holdings that stay ambiguous, or couldn't be looked up at all, are reported instead of
+/// guessed.
+pub async fn resolve_holdings(api: &StockApi, holdings: &[Trade]) -> CsvImport {
+    let mut import = CsvImport::default();
+
+    for holding in holdings {
+        let label = if holding.symbol.is_empty() {
+            &holding.name
+        } else {
+            &holding.symbol
+        };
+
+        match resolve_holding(api, holding).await {
+            Ok(Lookup::Found(ticker, name)) => import.entries.push(ImportEntry {
+                ticker,
+                name,
+                quantity: Some(holding.quantity),
+                cost: Some(holding.price),
+                // Statements have no tags, so the watchlist's are kept
+                tags: None,
+            }),
+            Ok(Lookup::Candidates(candidates)) if candidates.is_empty() => {
+                import.errors.push(format!("No ticker found for {}", label))
+            }
+            Ok(Lookup::Candidates(candidates)) => import.errors.push(format!(
+                "{} is ambiguous, could be {}",
+                label,
+                candidates
+                    .iter()
+                    .take(3)
+                    .map(|(symbol, _)| symbol.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            Err(_) => import.errors.push(format!(
+                "{} couldn't be checked, Yahoo didn't answer",
+                label
+            )),
+        }
+    }
+
+    import
+}
+
+/// Outcome of looking up the ticker of a holding
+enum Lookup {
+    /// Ticker and name
+    Found(String, String),
+    /// Symbols and names that could all be meant, if any
+    Candidates(Vec<(String, String)>),
+}
+
+/// Errors are for searches that failed, e.g. while offline
+async fn resolve_holding(api: &StockApi, holding: &Trade) -> Result<Lookup> {
+    if !holding.symbol.is_empty() {
+        let exact = api
+            .search_ticker(&holding.symbol)
+            .await?
+            .into_iter()
+            .find(|(symbol, _)| symbol.eq_ignore_ascii_case(&holding.symbol));
+        if let Some((ticker, name)) = exact {
+            return Ok(Lookup::Found(ticker, name));
+        }
+    }
+    if holding.name.is_empty() {
+        return Ok(Lookup::Candidates(vec![]));
+    }
+
+    let candidates = api.search_ticker(&holding.name).await?;
+    let resolved = match candidates.as_slice() {
+        [only] => Some(only.clone()),
+        _ => candidates
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(&holding.name))
+            .cloned(),
+    };
+    Ok(match resolved {
+        Some((ticker, name)) => Lookup::Found(ticker, name),
+        None => Lookup::Candidates(candidates),
+    })
+}
+
+fn detect_delimiter(data: &str) -> u8 {
+    let header = data.lines().next().unwrap_or("");
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|d| header.matches(*d as char).count())
+        .unwrap_or(b',')
+}
+
+/// Keeps the characters that make up a number, dropping currency symbols and spaces
+fn clean_amount(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-'))
+        .collect()
+}
+
+/// Whether the single comma in `cleaned` can only be a decimal comma. Thousands groups
+/// have exactly three digits and never follow a lone zero, so "0,125" and "12,5" can't
+/// be grouping while "1,234" could be either.
+fn is_decimal_comma(cleaned: &str, comma: usize) -> bool {
+    let integer = cleaned[..comma].trim_start_matches('-');
+    let decimals = cleaned.len() - comma - 1;
+    decimals != 3 || integer.is_empty() || integer == "0" || integer.len() > 3
+}
+
+/// Finds the decimal separator of a file from the values that leave no doubt about it,
+/// e.g. "1,234.56" or "12,5". `None` if they don't or if they disagree.
+fn detect_decimal_separator<'a>(values: impl Iterator<Item = &'a str>) -> Option<char> {
+    let mut found = None;
+    for value in values {
+        let cleaned = clean_amount(value);
+        let separator = match (cleaned.rfind('.'), cleaned.rfind(',')) {
+            (Some(dot), Some(comma)) => Some(if dot > comma { '.' } else { ',' }),
+            (None, Some(_)) if cleaned.matches(',').count() > 1 => Some('.'),
+            (None, Some(comma)) if is_decimal_comma(&cleaned, comma) => Some(','),
+            (Some(dot), None) if cleaned.matches('.').count() == 1 => {
+                (cleaned.len() - dot - 1 != 3).then_some('.')
+            }
+            _ => None,
+        };
+        match (found, separator) {
+            (_, None) => {}
+            (None, separator) => found = separator,
+            (Some(a), Some(b)) if a != b => return None,
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Parses numbers the way brokers export them, allowing currency symbols, thousands
+/// separators and decimal commas. `decimal` is the file's decimal separator if known,
+/// without it a lone comma before three digits is reported as ambiguous.
+fn parse_amount(value: &str, decimal: Option<char>) -> Result<f64> {
+    let cleaned = clean_amount(value);
+
+    let normalized = match (cleaned.rfind('.'), cleaned.rfind(',')) {
+        // "1,234.56"
+        (Some(dot), Some(comma)) if dot > comma => cleaned.replace(',', ""),
+        // "1.234,56"
+        (Some(_), Some(_)) => cleaned.replace('.', "").replace(',', "."),
+        // "12,345,678"
+        (None, Some(_)) if cleaned.matches(',').count() > 1 => cleaned.replace(',', ""),
+        // "12,5" and "0,125"
+        (None, Some(comma)) if is_decimal_comma(&cleaned, comma) => cleaned.replace(',', "."),
+        // "1,234"
+        (None, Some(_)) => match decimal {
+            Some(',') => cleaned.replace(',', "."),
+            Some(_) => cleaned.replace(',', ""),
+            None => {
+                return Err(anyhow!(
+                    "\"{}\" is ambiguous, the comma could separate decimals or thousands",
+                    value
+                ));
+            }
+        },
+        _ => cleaned,
+    };
+
+    normalized
+        .parse()
+        .map_err(|_| anyhow!("\"{}\" is not a number", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_thousands_separators() {
+        assert_eq!(parse_amount("1,234", Some('.')).ok(), Some(1234.0));
+        assert_eq!(parse_amount("$1,000", Some('.')).ok(), Some(1000.0));
+        assert_eq!(parse_amount("12,345,678", None).ok(), Some(12345678.0));
+        assert_eq!(parse_amount("1,234.56", None).ok(), Some(1234.56));
+        assert_eq!(parse_amount("1.234,56", None).ok(), Some(1234.56));
+    }
+
+    #[test]
+    fn parses_decimal_commas() {
+        assert_eq!(parse_amount("12,5", None).ok(), Some(12.5));
+        assert_eq!(parse_amount("1234,56", None).ok(), Some(1234.56));
+        assert_eq!(parse_amount("-0,25", None).ok(), Some(-0.25));
+        assert_eq!(parse_amount("0,125", None).ok(), Some(0.125));
+        assert_eq!(parse_amount("-0,250", None).ok(), Some(-0.25));
+        assert_eq!(parse_amount("1,234", Some(',')).ok(), Some(1.234));
+    }
+
+    #[test]
+    fn reports_ambiguous_commas() {
+        assert!(parse_amount("1,234", None).is_err());
+        assert!(parse_amount("-12,500", None).is_err());
+    }
+
+    #[test]
+    fn detects_the_decimal_separator() {
+        assert_eq!(
+            detect_decimal_separator(["1,000", "150.25"].into_iter()),
+            Some('.')
+        );
+        assert_eq!(
+            detect_decimal_separator(["1,000", "150,25"].into_iter()),
+            Some(',')
+        );
+        assert_eq!(
+            detect_decimal_separator(["1,000", "2,500"].into_iter()),
+            None
+        );
+        assert_eq!(detect_decimal_separator(["1.5", "2,5"].into_iter()), None);
+    }
+}
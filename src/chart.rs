@@ -0,0 +1,768 @@
+use crate::{stock_api::Candle, stock_manager::format_compact};
+use gtk::{
+    Box, DrawingArea, EventControllerMotion, Label, Orientation, Widget,
+    cairo::{Context, FontSlant, FontWeight},
+    gdk::RGBA,
+    glib::{self, DateTime},
+    prelude::*,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// Space right of the plot for the price axis
+const AXIS_WIDTH: f64 = 56.0;
+/// Space below the plot for the first and last date
+const DATE_HEIGHT: f64 = 16.0;
+const PADDING: f64 = 8.0;
+const PRICE_TICKS: usize = 4;
+/// Share of the plot height taken by the volume pane in candle mode
+const VOLUME_SHARE: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChartMode {
+    #[default]
+    Line,
+    /// OHLC candles with a volume histogram below them
+    Candles,
+}
+
+/// A price chart, with a crosshair and readout that follow the pointer
+#[derive(Clone)]
+pub struct PriceChart {
+    container: Box,
+    area: DrawingArea,
+    readout: Label,
+    state: Rc<RefCell<ChartState>>,
+}
+
+#[derive(Default)]
+struct ChartState {
+    candles: Vec<Candle>,
+    mode: ChartMode,
+    /// Whether timestamps are shown with the time of day
+    intraday: bool,
+    /// Index of the candle under the pointer
+    hover: Option<usize>,
+}
+
+impl Default for PriceChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PriceChart {
+    pub fn new() -> Self {
+        let area = DrawingArea::builder()
+            .content_height(240)
+            .hexpand(true)
+            .vexpand(true)
+            .build();
+        let readout = Label::builder()
+            .xalign(0.0)
+            .css_classes(["numeric", "dim-label"])
+            .build();
+
+        let container = Box::new(Orientation::Vertical, 6);
+        container.append(&readout);
+        container.append(&area);
+        let theme_colors = ThemeColors::new(&container);
+
+        let chart = Self {
+            container,
+            area,
+            readout,
+            state: Rc::new(RefCell::new(ChartState::default())),
+        };
+
+        chart.area.set_draw_func(glib::clone!(
+            #[weak(rename_to = state)]
+            chart.state,
+            move |area, cr, width, height| {
+                // The area's own colour is the `success`/`error` colour of the overall
+                // direction, also used for the axes
+                let colors = theme_colors.colors(area);
+                draw(cr, &state.borrow(), &colors, width as f64, height as f64);
+            }
+        ));
+
+        let motion = EventControllerMotion::new();
+        motion.connect_motion(glib::clone!(
+            #[weak(rename_to = area)]
+            chart.area,
+            #[weak(rename_to = readout)]
+            chart.readout,
+            #[weak(rename_to = state)]
+            chart.state,
+            move |_, x, _| {
+                let hover = plot_index(state.borrow().candles.len(), x, area.width() as f64);
+                set_hover(&area, &readout, &state, hover);
+            }
+        ));
+        motion.connect_leave(glib::clone!(
+            #[weak(rename_to = area)]
+            chart.area,
+            #[weak(rename_to = readout)]
+            chart.readout,
+            #[weak(rename_to = state)]
+            chart.state,
+            move |_| set_hover(&area, &readout, &state, None)
+        ));
+        chart.area.add_controller(motion);
+
+        chart
+    }
+
+    pub fn widget(&self) -> &Box {
+        &self.container
+    }
+
+    /// Replaces the plotted prices, `intraday` adds the time of day to the readout
+    pub fn set_candles(&self, candles: Vec<Candle>, intraday: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.candles = candles;
+            state.intraday = intraday;
+            state.hover = None;
+        }
+
+        let state = self.state.borrow();
+        update_readout(&self.readout, &state);
+        let rising = match (state.candles.first(), state.candles.last()) {
+            (Some(first), Some(last)) => last.close >= first.close,
+            _ => true,
+        };
+        if rising {
+            self.area.add_css_class("success");
+            self.area.remove_css_class("error");
+        } else {
+            self.area.add_css_class("error");
+            self.area.remove_css_class("success");
+        }
+        drop(state);
+        self.area.queue_draw();
+    }
+
+    pub fn set_mode(&self, mode: ChartMode) {
+        self.state.borrow_mut().mode = mode;
+        update_readout(&self.readout, &self.state.borrow());
+        self.area.queue_draw();
+    }
+
+    /// Clears the chart and shows `message` instead, e.g. while loading
+    pub fn set_message(&self, message: &str) {
+        self.state.borrow_mut().candles.clear();
+        self.readout.set_text(message);
+        self.area.queue_draw();
+    }
+}
+
+fn set_hover(
+    area: &DrawingArea,
+    readout: &Label,
+    state: &RefCell<ChartState>,
+    hover: Option<usize>,
+) {
+    if state.borrow().hover == hover {
+        return;
+    }
+    state.borrow_mut().hover = hover;
+    update_readout(readout, &state.borrow());
+    area.queue_draw();
+}
+
+/// Shows the candle under the pointer, or the change over the whole range
+fn update_readout(readout: &Label, state: &ChartState) {
+    let (Some(first), Some(last)) = (state.candles.first(), state.candles.last()) else {
+        readout.set_text("");
+        return;
+    };
+
+    let text = match state.hover.and_then(|i| state.candles.get(i)) {
+        Some(candle) if state.mode == ChartMode::Candles => format!(
+            "{}  O {:.2}  H {:.2}  L {:.2}  C {:.2}  Vol {}",
+            format_timestamp(candle.timestamp, state.intraday),
+            candle.open,
+            candle.high,
+            candle.low,
+            candle.close,
+            format_compact(candle.volume)
+        ),
+        Some(candle) => format!(
+            "{}  {:.2}",
+            format_timestamp(candle.timestamp, state.intraday),
+            candle.close
+        ),
+        None => {
+            let change = (last.close - first.close) / first.close * 100.0;
+            let sign = if change >= 0.0 { "+" } else { "" };
+            format!("{:.2}  {}{:.2}% over this range", last.close, sign, change)
+        }
+    };
+    readout.set_text(&text);
+}
+
+/// Maps between prices and candle indices and positions on the drawing area. Every
+/// candle gets an equal slot of the width and is drawn at its centre.
+struct Plot {
+    width: f64,
+    /// Bottom of the price pane
+    price_bottom: f64,
+    /// Top and bottom of the volume pane, equal when there is none
+    volume_top: f64,
+    volume_bottom: f64,
+    slot: f64,
+    min: f64,
+    max: f64,
+    max_volume: f64,
+}
+
+impl Plot {
+    fn new(state: &ChartState, width: f64, height: f64) -> Self {
+        let candles = &state.candles;
+        let (lows, highs): (Vec<f64>, Vec<f64>) = match state.mode {
+            ChartMode::Line => candles.iter().map(|c| (c.close, c.close)).unzip(),
+            ChartMode::Candles => candles.iter().map(|c| (c.low, c.high)).unzip(),
+        };
+        let min = lows.into_iter().fold(f64::INFINITY, f64::min);
+        let max = highs.into_iter().fold(f64::NEG_INFINITY, f64::max);
+
+        let bottom = height - DATE_HEIGHT;
+        let (price_bottom, volume_top) = match state.mode {
+            ChartMode::Line => (bottom, bottom),
+            ChartMode::Candles => {
+                let volume_height = (bottom * VOLUME_SHARE).round();
+                (bottom - volume_height - 4.0, bottom - volume_height)
+            }
+        };
+
+        let plot_width = width - AXIS_WIDTH;
+        Self {
+            width: plot_width,
+            price_bottom,
+            volume_top,
+            volume_bottom: bottom,
+            slot: plot_width / candles.len().max(1) as f64,
+            min,
+            max: if max > min { max } else { min + 1.0 },
+            max_volume: candles.iter().map(|c| c.volume).fold(0.0, f64::max),
+        }
+    }
+
+    fn x(&self, index: usize) -> f64 {
+        (index as f64 + 0.5) * self.slot
+    }
+
+    fn y(&self, price: f64) -> f64 {
+        PADDING + (self.max - price) / (self.max - self.min) * (self.price_bottom - 2.0 * PADDING)
+    }
+
+    fn volume_y(&self, volume: f64) -> f64 {
+        if self.max_volume <= 0.0 {
+            return self.volume_bottom;
+        }
+        self.volume_bottom - volume / self.max_volume * (self.volume_bottom - self.volume_top)
+    }
+}
+
+/// Index of the candle under `x`, if `x` is over the plot
+fn plot_index(count: usize, x: f64, width: f64) -> Option<usize> {
+    let plot_width = width - AXIS_WIDTH;
+    if count == 0 || x < 0.0 || x > plot_width {
+        return None;
+    }
+    let index = (x / plot_width * count as f64) as usize;
+    Some(index.min(count - 1))
+}
+
+fn format_timestamp(timestamp: i64, intraday: bool) -> String {
+    let format = if intraday {
+        "%Y-%m-%d %H:%M"
+    } else {
+        "%Y-%m-%d"
+    };
+    DateTime::from_unix_local(timestamp)
+        .and_then(|dt| dt.format(format))
+        .map(|s| s.to_string())
+        .unwrap_or_default()
+}
+
+/// Hidden labels that only carry the theme's colours for gains and losses, so drawings
+/// of prices follow the theme
+pub struct ThemeColors {
+    rising: Label,
+    falling: Label,
+}
+
+impl ThemeColors {
+    /// The labels have to be in the widget tree to be styled, so they're added to `parent`
+    pub fn new(parent: &Box) -> Self {
+        let label = |class: &str| Label::builder().visible(false).css_classes([class]).build();
+        let colors = Self {
+            rising: label("success"),
+            falling: label("error"),
+        };
+        parent.append(&colors.rising);
+        parent.append(&colors.falling);
+        colors
+    }
+
+    /// The current colours, with the foreground taken from `widget`
+    pub fn colors(&self, widget: &impl IsA<Widget>) -> Colors {
+        Colors {
+            foreground: widget.color(),
+            rising: self.rising.color(),
+            falling: self.falling.color(),
+        }
+    }
+}
+
+/// Colours taken from CSS, see `ThemeColors`
+pub struct Colors {
+    pub foreground: RGBA,
+    pub rising: RGBA,
+    pub falling: RGBA,
+}
+
+pub fn set_color(cr: &Context, color: &RGBA, alpha: f64) {
+    cr.set_source_rgba(
+        color.red() as f64,
+        color.green() as f64,
+        color.blue() as f64,
+        alpha,
+    );
+}
+
+fn draw(cr: &Context, state: &ChartState, colors: &Colors, width: f64, height: f64) {
+    let candles = &state.candles;
+    if candles.len() < 2 {
+        return;
+    }
+
+    let plot = Plot::new(state, width, height);
+
+    cr.select_font_face("Sans", FontSlant::Normal, FontWeight::Normal);
+    cr.set_font_size(10.0);
+    cr.set_line_width(1.0);
+
+    // Price grid with labels on the right
+    for tick in 0..=PRICE_TICKS {
+        let price = plot.min + (plot.max - plot.min) * tick as f64 / PRICE_TICKS as f64;
+        let y = plot.y(price).round() + 0.5;
+        set_color(cr, &colors.foreground, 0.15);
+        cr.move_to(0.0, y);
+        cr.line_to(plot.width, y);
+        let _ = cr.stroke();
+
+        set_color(cr, &colors.foreground, 0.7);
+        cr.move_to(plot.width + 4.0, y + 3.0);
+        let _ = cr.show_text(&format!("{:.2}", price));
+    }
+
+    // First and last date below the plot
+    let first = format_timestamp(candles[0].timestamp, state.intraday);
+    let last = format_timestamp(candles[candles.len() - 1].timestamp, state.intraday);
+    cr.move_to(0.0, height - 4.0);
+    let _ = cr.show_text(&first);
+    if let Ok(extents) = cr.text_extents(&last) {
+        cr.move_to(plot.width - extents.width(), height - 4.0);
+        let _ = cr.show_text(&last);
+    }
+
+    match state.mode {
+        ChartMode::Line => draw_line(cr, candles, &plot, &colors.foreground),
+        ChartMode::Candles => draw_candles(cr, candles, &plot, colors),
+    }
+
+    if let Some(index) = state.hover
+        && let Some(candle) = candles.get(index)
+    {
+        let x = plot.x(index).round() + 0.5;
+        let y = plot.y(candle.close);
+
+        set_color(cr, &colors.foreground, 0.5);
+        cr.set_line_width(1.0);
+        cr.set_dash(&[3.0, 3.0], 0.0);
+        cr.move_to(x, 0.0);
+        cr.line_to(x, plot.volume_bottom);
+        cr.move_to(0.0, y);
+        cr.line_to(plot.width, y);
+        let _ = cr.stroke();
+        cr.set_dash(&[], 0.0);
+
+        set_color(cr, &colors.foreground, 1.0);
+        cr.arc(x, y, 3.0, 0.0, std::f64::consts::TAU);
+        let _ = cr.fill();
+    }
+}
+
+/// The closing prices as a line, with a faint fill below it
+fn draw_line(cr: &Context, candles: &[Candle], plot: &Plot, color: &RGBA) {
+    for (i, candle) in candles.iter().enumerate() {
+        cr.line_to(plot.x(i), plot.y(candle.close));
+    }
+    set_color(cr, color, 1.0);
+    cr.set_line_width(1.5);
+    let _ = cr.stroke_preserve();
+    cr.line_to(plot.x(candles.len() - 1), plot.price_bottom);
+    cr.line_to(plot.x(0), plot.price_bottom);
+    cr.close_path();
+    set_color(cr, color, 0.1);
+    let _ = cr.fill();
+}
+
+fn draw_candles(cr: &Context, candles: &[Candle], plot: &Plot, colors: &Colors) {
+    let body_width = (plot.slot * 0.7).max(1.0);
+    cr.set_line_width(1.0);
+
+    for (i, candle) in candles.iter().enumerate() {
+        let color = if candle.close >= candle.open {
+            &colors.rising
+        } else {
+            &colors.falling
+        };
+        let x = plot.x(i);
+        let wick_x = x.round() + 0.5;
+
+        set_color(cr, color, 1.0);
+        cr.move_to(wick_x, plot.y(candle.high));
+        cr.line_to(wick_x, plot.y(candle.low));
+        let _ = cr.stroke();
+
+        // At least a pixel high, so unchanged candles still show
+        let top = plot.y(candle.open.max(candle.close));
+        let bottom = plot.y(candle.open.min(candle.close));
+        cr.rectangle(
+            x - body_width / 2.0,
+            top,
+            body_width,
+            (bottom - top).max(1.0),
+        );
+        let _ = cr.fill();
+
+        set_color(cr, color, 0.4);
+        let volume_y = plot.volume_y(candle.volume);
+        cr.rectangle(
+            x - body_width / 2.0,
+            volume_y,
+            body_width,
+            plot.volume_bottom - volume_y,
+        );
+        let _ = cr.fill();
+    }
+}
+
+/// Colours of the compared series, in order, as RGB
+const SERIES_COLORS: [(f64, f64, f64); 6] = [
+    (0.21, 0.52, 0.89),
+    (0.90, 0.38, 0.00),
+    (0.57, 0.25, 0.66),
+    (0.18, 0.76, 0.49),
+    (0.88, 0.11, 0.14),
+    (0.60, 0.45, 0.30),
+];
+
+/// Several tickers on one chart, each rebased to 0% at the start of its history
+#[derive(Clone)]
+pub struct ComparisonChart {
+    container: Box,
+    area: DrawingArea,
+    readout: Label,
+    state: Rc<RefCell<ComparisonState>>,
+}
+
+#[derive(Default)]
+struct ComparisonState {
+    series: Vec<Series>,
+    intraday: bool,
+    /// Timestamp under the pointer
+    hover: Option<i64>,
+}
+
+struct Series {
+    ticker: String,
+    /// Timestamps with the change since the first close, as a fraction
+    points: Vec<(i64, f64)>,
+}
+
+impl Series {
+    fn new(ticker: String, candles: &[Candle]) -> Self {
+        let first = candles.first().map(|c| c.close).unwrap_or_default();
+        let points = if first > 0.0 {
+            candles
+                .iter()
+                .map(|c| (c.timestamp, c.close / first - 1.0))
+                .collect()
+        } else {
+            vec![]
+        };
+        Self { ticker, points }
+    }
+
+    /// The change at `timestamp`, i.e. at the last point before it
+    fn change_at(&self, timestamp: i64) -> Option<f64> {
+        let index = self.points.partition_point(|(t, _)| *t <= timestamp);
+        self.points.get(index.saturating_sub(1)).map(|(_, c)| *c)
+    }
+}
+
+impl Default for ComparisonChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ComparisonChart {
+    pub fn new() -> Self {
+        let area = DrawingArea::builder()
+            .content_height(280)
+            .hexpand(true)
+            .vexpand(true)
+            .build();
+        let readout = Label::builder()
+            .xalign(0.0)
+            .wrap(true)
+            .css_classes(["numeric", "dim-label"])
+            .build();
+
+        let container = Box::new(Orientation::Vertical, 6);
+        container.append(&readout);
+        container.append(&area);
+
+        let chart = Self {
+            container,
+            area,
+            readout,
+            state: Rc::new(RefCell::new(ComparisonState::default())),
+        };
+
+        chart.area.set_draw_func(glib::clone!(
+            #[weak(rename_to = state)]
+            chart.state,
+            move |area, cr, width, height| {
+                draw_comparison(
+                    cr,
+                    &state.borrow(),
+                    &area.color(),
+                    width as f64,
+                    height as f64,
+                );
+            }
+        ));
+
+        let motion = EventControllerMotion::new();
+        motion.connect_motion(glib::clone!(
+            #[weak(rename_to = area)]
+            chart.area,
+            #[weak(rename_to = readout)]
+            chart.readout,
+            #[weak(rename_to = state)]
+            chart.state,
+            move |_, x, _| {
+                let hover = TimeSpan::new(&state.borrow().series)
+                    .and_then(|span| span.timestamp_at(x, area.width() as f64 - AXIS_WIDTH));
+                set_comparison_hover(&area, &readout, &state, hover);
+            }
+        ));
+        motion.connect_leave(glib::clone!(
+            #[weak(rename_to = area)]
+            chart.area,
+            #[weak(rename_to = readout)]
+            chart.readout,
+            #[weak(rename_to = state)]
+            chart.state,
+            move |_| set_comparison_hover(&area, &readout, &state, None)
+        ));
+        chart.area.add_controller(motion);
+
+        chart
+    }
+
+    pub fn widget(&self) -> &Box {
+        &self.container
+    }
+
+    /// Replaces the plotted tickers, `intraday` adds the time of day to the readout
+    pub fn set_series(&self, series: Vec<(String, Vec<Candle>)>, intraday: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.series = series
+                .into_iter()
+                .map(|(ticker, candles)| Series::new(ticker, &candles))
+                .filter(|series| !series.points.is_empty())
+                .collect();
+            state.intraday = intraday;
+            state.hover = None;
+        }
+        update_comparison_readout(&self.readout, &self.state.borrow());
+        self.area.queue_draw();
+    }
+
+    /// Clears the chart and shows `message` instead, e.g. while loading
+    pub fn set_message(&self, message: &str) {
+        self.state.borrow_mut().series.clear();
+        self.readout.set_text(message);
+        self.area.queue_draw();
+    }
+}
+
+fn set_comparison_hover(
+    area: &DrawingArea,
+    readout: &Label,
+    state: &RefCell<ComparisonState>,
+    hover: Option<i64>,
+) {
+    if state.borrow().hover == hover {
+        return;
+    }
+    state.borrow_mut().hover = hover;
+    update_comparison_readout(readout, &state.borrow());
+    area.queue_draw();
+}
+
+/// Shows the change of every ticker at the pointer, or at the end of the range
+fn update_comparison_readout(readout: &Label, state: &ComparisonState) {
+    let Some(span) = TimeSpan::new(&state.series) else {
+        readout.set_text("");
+        return;
+    };
+
+    let timestamp = state.hover.unwrap_or(span.end);
+    let changes: Vec<String> = state
+        .series
+        .iter()
+        .filter_map(|series| {
+            let change = series.change_at(timestamp)? * 100.0;
+            let sign = if change >= 0.0 { "+" } else { "" };
+            Some(format!("{} {}{:.2}%", series.ticker, sign, change))
+        })
+        .collect();
+
+    readout.set_text(&format!(
+        "{}  {}",
+        format_timestamp(timestamp, state.intraday),
+        changes.join("  ")
+    ));
+}
+
+/// The time covered by all series together, the x axis of the comparison
+struct TimeSpan {
+    start: i64,
+    end: i64,
+}
+
+impl TimeSpan {
+    fn new(series: &[Series]) -> Option<Self> {
+        let start = series
+            .iter()
+            .filter_map(|s| s.points.first())
+            .map(|p| p.0)
+            .min()?;
+        let end = series
+            .iter()
+            .filter_map(|s| s.points.last())
+            .map(|p| p.0)
+            .max()?;
+        Some(Self {
+            start,
+            end: end.max(start + 1),
+        })
+    }
+
+    fn x(&self, timestamp: i64, width: f64) -> f64 {
+        (timestamp - self.start) as f64 / (self.end - self.start) as f64 * width
+    }
+
+    fn timestamp_at(&self, x: f64, width: f64) -> Option<i64> {
+        if x < 0.0 || x > width {
+            return None;
+        }
+        Some(self.start + (x / width * (self.end - self.start) as f64) as i64)
+    }
+}
+
+fn draw_comparison(cr: &Context, state: &ComparisonState, axis: &RGBA, width: f64, height: f64) {
+    let Some(span) = TimeSpan::new(&state.series) else {
+        return;
+    };
+
+    let plot_width = width - AXIS_WIDTH;
+    let plot_height = height - DATE_HEIGHT;
+    let changes = state
+        .series
+        .iter()
+        .flat_map(|s| s.points.iter().map(|p| p.1));
+    let (min, max) = changes.fold((0.0_f64, 0.0_f64), |(min, max), c| (min.min(c), max.max(c)));
+    let max = if max > min { max } else { min + 0.01 };
+    let y = |change: f64| PADDING + (max - change) / (max - min) * (plot_height - 2.0 * PADDING);
+
+    cr.select_font_face("Sans", FontSlant::Normal, FontWeight::Normal);
+    cr.set_font_size(10.0);
+    cr.set_line_width(1.0);
+
+    for tick in 0..=PRICE_TICKS {
+        let change = min + (max - min) * tick as f64 / PRICE_TICKS as f64;
+        let tick_y = y(change).round() + 0.5;
+        set_color(cr, axis, 0.15);
+        cr.move_to(0.0, tick_y);
+        cr.line_to(plot_width, tick_y);
+        let _ = cr.stroke();
+
+        set_color(cr, axis, 0.7);
+        cr.move_to(plot_width + 4.0, tick_y + 3.0);
+        let sign = if change >= 0.0 { "+" } else { "" };
+        let _ = cr.show_text(&format!("{}{:.1}%", sign, change * 100.0));
+    }
+
+    // The common starting point
+    set_color(cr, axis, 0.5);
+    let zero = y(0.0).round() + 0.5;
+    cr.move_to(0.0, zero);
+    cr.line_to(plot_width, zero);
+    let _ = cr.stroke();
+
+    set_color(cr, axis, 0.7);
+    cr.move_to(0.0, height - 4.0);
+    let _ = cr.show_text(&format_timestamp(span.start, state.intraday));
+    let last = format_timestamp(span.end, state.intraday);
+    if let Ok(extents) = cr.text_extents(&last) {
+        cr.move_to(plot_width - extents.width(), height - 4.0);
+        let _ = cr.show_text(&last);
+    }
+
+    cr.set_line_width(1.5);
+    for (i, series) in state.series.iter().enumerate() {
+        let (r, g, b) = SERIES_COLORS[i % SERIES_COLORS.len()];
+        cr.set_source_rgb(r, g, b);
+        for (timestamp, change) in &series.points {
+            cr.line_to(span.x(*timestamp, plot_width), y(*change));
+        }
+        let _ = cr.stroke();
+
+        // Legend in the top left corner
+        let legend_y = PADDING + 12.0 * i as f64;
+        cr.rectangle(PADDING, legend_y + 2.0, 8.0, 8.0);
+        let _ = cr.fill();
+        cr.move_to(PADDING + 12.0, legend_y + 10.0);
+        let _ = cr.show_text(&series.ticker);
+    }
+
+    if let Some(timestamp) = state.hover {
+        let x = span.x(timestamp, plot_width).round() + 0.5;
+        set_color(cr, axis, 0.5);
+        cr.set_line_width(1.0);
+        cr.set_dash(&[3.0, 3.0], 0.0);
+        cr.move_to(x, 0.0);
+        cr.line_to(x, plot_height);
+        let _ = cr.stroke();
+        cr.set_dash(&[], 0.0);
+
+        for (i, series) in state.series.iter().enumerate() {
+            if let Some(change) = series.change_at(timestamp) {
+                let (r, g, b) = SERIES_COLORS[i % SERIES_COLORS.len()];
+                cr.set_source_rgb(r, g, b);
+                cr.arc(x, y(change), 3.0, 0.0, std::f64::consts::TAU);
+                let _ = cr.fill();
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    csv_io::{read_csv, write_csv},
+    persistence::{load_tickers, merge_entries, save_tickers},
+};
+use std::path::Path;
+
+const USAGE: &str = "Usage: stockfin [--import <file.csv> | --export <file.csv>]";
+
+/// Handles command line options that run without opening the window.
+/// Returns the exit code if the arguments were handled, `None` to start the app normally.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args {
+        [flag, path] if flag == "--import" => Some(import(Path::new(path))),
+        [flag, path] if flag == "--export" => Some(export(Path::new(path))),
+        [flag] if flag == "--import" || flag == "--export" => {
+            eprintln!("{}", USAGE);
+            Some(2)
+        }
+        _ => None,
+    }
+}
+
+fn import(path: &Path) -> i32 {
+    let import = match read_csv(path) {
+        Ok(import) => import,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    for error in &import.errors {
+        eprintln!("Skipped {}", error);
+    }
+
+    let mut tickers = load_tickers();
+    merge_entries(&mut tickers, &import.entries);
+    if let Err(e) = save_tickers(&tickers) {
+        eprintln!("{}", e);
+        return 1;
+    }
+
+    println!(
+        "Imported {} tickers, skipped {}",
+        import.entries.len(),
+        import.errors.len()
+    );
+    0
+}
+
+fn export(path: &Path) -> i32 {
+    let tickers = load_tickers();
+    match write_csv(path, &tickers) {
+        Ok(()) => {
+            println!("Exported {} tickers to {}", tickers.len(), path.display());
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{chart::ComparisonChart, detail_window::RANGES, stock_api::StockApi};
+use gtk::{Align, Box, Entry, Label, Orientation, ToggleButton, Window, glib, prelude::*};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+/// Charts the change of `tickers` since the start of the selected range, more tickers,
+/// e.g. an index to compare against, can be added in the window
+pub fn show_compare_window(parent: Option<&Window>, tickers: Vec<String>, api: Rc<StockApi>) {
+    let layout = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let toolbar = Box::new(Orientation::Horizontal, 12);
+    let range_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .css_classes(["linked"])
+        .build();
+    toolbar.append(&range_box);
+    let add_entry = Entry::builder()
+        .placeholder_text("Add ticker, e.g. ^GSPC")
+        .halign(Align::End)
+        .hexpand(true)
+        .build();
+    toolbar.append(&add_entry);
+    layout.append(&toolbar);
+
+    let chart = ComparisonChart::new();
+    layout.append(chart.widget());
+
+    let status = Label::builder()
+        .xalign(0.0)
+        .wrap(true)
+        .css_classes(["dim-label"])
+        .visible(false)
+        .build();
+    layout.append(&status);
+
+    let tickers = Rc::new(RefCell::new(tickers));
+    // Index into RANGES of the selected range
+    let range_index = Rc::new(Cell::new(0));
+
+    let load = glib::clone!(
+        #[strong]
+        chart,
+        #[weak]
+        status,
+        #[strong]
+        tickers,
+        #[strong]
+        range_index,
+        move || {
+            let (_, range, interval) = RANGES[range_index.get()];
+            let intraday = !interval.ends_with('d') && !interval.ends_with("wk");
+            let tickers = tickers.borrow().clone();
+
+            chart.set_message("Loading…");
+            // Let the window redraw before the fetches block the main loop
+            glib::MainContext::default().spawn_local(glib::clone!(
+                #[strong]
+                chart,
+                #[strong]
+                api,
+                async move {
+                    let rt = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .unwrap();
+
+                    let mut series = vec![];
+                    let mut failed = vec![];
+                    for ticker in tickers {
+                        // Same as the list updates, the API needs a tokio runtime of its own
+                        match rt.block_on(api.history(&ticker, interval, range)) {
+                            Ok(history) => series.push((ticker, history.candles)),
+                            Err(_) => failed.push(ticker),
+                        }
+                    }
+
+                    status.set_visible(!failed.is_empty());
+                    status.set_text(&format!("No prices for {}", failed.join(", ")));
+                    chart.set_series(series, intraday);
+                }
+            ));
+        }
+    );
+
+    let mut buttons: Vec<ToggleButton> = vec![];
+    for (index, (label, _, _)) in RANGES.into_iter().enumerate() {
+        let button = ToggleButton::with_label(label);
+        button.set_group(buttons.first());
+        button.connect_toggled(glib::clone!(
+            #[strong]
+            range_index,
+            #[strong]
+            load,
+            move |button| {
+                if button.is_active() {
+                    range_index.set(index);
+                    load();
+                }
+            }
+        ));
+        range_box.append(&button);
+        buttons.push(button);
+    }
+
+    add_entry.connect_activate(glib::clone!(
+        #[strong]
+        tickers,
+        #[strong]
+        load,
+        move |entry| {
+            let ticker = entry.text().trim().to_uppercase();
+            if ticker.is_empty() {
+                return;
+            }
+            entry.set_text("");
+            if !tickers.borrow().contains(&ticker) {
+                tickers.borrow_mut().push(ticker);
+                load();
+            }
+        }
+    ));
+
+    let window = Window::builder()
+        .title("Compare")
+        .default_width(720)
+        .default_height(480)
+        .child(&layout)
+        .build();
+    window.set_transient_for(parent);
+    window.present();
+
+    // A year is the most common comparison
+    let default_range = RANGES.iter().position(|(label, _, _)| *label == "1Y");
+    if let Some(button) = default_range.and_then(|index| buttons.get(index)) {
+        button.set_active(true);
+    }
+}
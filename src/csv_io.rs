@@ -0,0 +1,142 @@
+use crate::persistence::{ImportEntry, TickerEntry};
+use anyhow::{Result, anyhow};
+use std::{
+    collections::HashSet,
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+pub const CSV_HEADER: [&str; 5] = ["ticker", "name", "quantity", "cost", "tags"];
+
+/// Tags are stored in a single column, separated by this character
+const TAG_SEPARATOR: char = ';';
+
+/// The outcome of parsing a CSV file, valid rows are kept even if others fail
+#[derive(Debug, Default)]
+pub struct CsvImport {
+    pub entries: Vec<ImportEntry>,
+    pub errors: Vec<String>,
+}
+
+pub fn read_csv(path: &Path) -> Result<CsvImport> {
+    let data =
+        read_to_string(path).map_err(|e| anyhow!("Could not read {}: {}", path.display(), e))?;
+    parse_csv(&data)
+}
+
+pub fn write_csv(path: &Path, entries: &[TickerEntry]) -> Result<()> {
+    write(path, to_csv(entries)?).map_err(|e| anyhow!("Could not write {}: {}", path.display(), e))
+}
+
+/// Parses a watchlist CSV, columns are matched by header name in any order. Columns the
+/// file doesn't have are left `None`, so importing doesn't touch those values.
+pub fn parse_csv(data: &str) -> Result<CsvImport> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(data.as_bytes());
+
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_lowercase()).collect();
+    let column = |name: &str| headers.iter().position(|h| h == name);
+
+    let Some(ticker_col) = column("ticker") else {
+        return Err(anyhow!("Missing \"ticker\" column in header row"));
+    };
+    let name_col = column("name");
+    let quantity_col = column("quantity");
+    let cost_col = column("cost");
+    let tags_col = column("tags");
+
+    let mut import = CsvImport::default();
+    let mut seen = HashSet::new();
+
+    for (i, record) in reader.records().enumerate() {
+        // Header is line 1
+        let line = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                import.errors.push(format!("Line {}: {}", line, e));
+                continue;
+            }
+        };
+        let field = |col: Option<usize>| col.and_then(|c| record.get(c)).unwrap_or("");
+
+        let ticker = field(Some(ticker_col)).to_uppercase();
+        if ticker.is_empty() {
+            import.errors.push(format!("Line {}: missing ticker", line));
+            continue;
+        }
+        if !seen.insert(ticker.clone()) {
+            import
+                .errors
+                .push(format!("Line {}: duplicate ticker {}", line, ticker));
+            continue;
+        }
+
+        let quantity = match quantity_col
+            .map(|c| parse_number(field(Some(c))))
+            .transpose()
+        {
+            Ok(quantity) => quantity,
+            Err(e) => {
+                import.errors.push(format!("Line {}: quantity {}", line, e));
+                continue;
+            }
+        };
+        let cost = match cost_col.map(|c| parse_number(field(Some(c)))).transpose() {
+            Ok(cost) => cost,
+            Err(e) => {
+                import.errors.push(format!("Line {}: cost {}", line, e));
+                continue;
+            }
+        };
+
+        let tags = tags_col.map(|c| {
+            field(Some(c))
+                .split(TAG_SEPARATOR)
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect()
+        });
+
+        import.entries.push(ImportEntry {
+            name: field(name_col).to_string(),
+            ticker,
+            quantity,
+            cost,
+            tags,
+        });
+    }
+
+    Ok(import)
+}
+
+pub fn to_csv(entries: &[TickerEntry]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(CSV_HEADER)?;
+
+    for entry in entries {
+        writer.write_record([
+            entry.ticker.clone(),
+            entry.name.clone(),
+            entry.quantity.to_string(),
+            entry.cost.to_string(),
+            entry.tags.join(&TAG_SEPARATOR.to_string()),
+        ])?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Empty cells count as zero, anything else has to be a non-negative number
+fn parse_number(value: &str) -> Result<f64> {
+    if value.is_empty() {
+        return Ok(0.0);
+    }
+    match value.parse::<f64>() {
+        Ok(n) if n.is_finite() && n >= 0.0 => Ok(n),
+        _ => Err(anyhow!("\"{}\" is not a valid number", value)),
+    }
+}
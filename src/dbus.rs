@@ -22,12 +22,17 @@ trait Stockfin {
 
 pub struct StockfinBusState {
     pub avg_change: Arc<AtomicF64>,
+    /// Sentiment thresholds in percent, kept in sync with the settings
+    pub bullish_threshold: Arc<AtomicF64>,
+    pub bearish_threshold: Arc<AtomicF64>,
 }
 
 impl StockfinBusState {
     fn new() -> Self {
         Self {
             avg_change: Arc::new(AtomicF64::new(0.0)),
+            bullish_threshold: Arc::new(AtomicF64::new(5.0)),
+            bearish_threshold: Arc::new(AtomicF64::new(-5.0)),
         }
     }
 }
@@ -57,9 +62,11 @@ impl StockfinBus {
     fn status_json(&self) -> String {
         let val = self.state.avg_change.load(Ordering::Acquire);
         let percentage = val * 100.0;
-        let class = if percentage >= 5.0 {
+        let bullish = self.state.bullish_threshold.load(Ordering::Acquire);
+        let bearish = self.state.bearish_threshold.load(Ordering::Acquire);
+        let class = if percentage >= bullish {
             "bullish"
-        } else if percentage < -5.0 {
+        } else if percentage < bearish {
             "bearish"
         } else {
             "neutral"
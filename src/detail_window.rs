@@ -0,0 +1,237 @@
+use crate::{
+    chart::{ChartMode, PriceChart},
+    stock_api::{History, StockApi},
+    stock_manager::format_compact,
+    stock_object::StockObject,
+};
+use gtk::{Align, Box, DropDown, Grid, Label, Orientation, ToggleButton, Window, glib, prelude::*};
+use std::{cell::Cell, rc::Rc};
+
+/// Intervals that can be picked for the chart, using Yahoo's names
+pub const INTERVALS: [&str; 5] = ["1m", "5m", "1h", "1d", "1wk"];
+
+/// Chart ranges as button label, Yahoo range and the interval that gives a readable
+/// number of points for it, picking a range also picks its interval
+pub const RANGES: [(&str, &str, &str); 7] = [
+    ("1D", "1d", "5m"),
+    ("5D", "5d", "5m"),
+    ("1M", "1mo", "1h"),
+    ("6M", "6mo", "1d"),
+    ("1Y", "1y", "1d"),
+    ("5Y", "5y", "1wk"),
+    ("Max", "max", "1wk"),
+];
+
+/// Shows a price chart of `stock` with its key stats
+pub fn show_detail_window(parent: Option<&Window>, stock: &StockObject, api: Rc<StockApi>) {
+    let layout = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let title = Label::builder()
+        .label(format!("{} ({})", stock.title(), stock.ticker()))
+        .xalign(0.0)
+        .css_classes(["title-2"])
+        .build();
+    layout.append(&title);
+
+    let toolbar = Box::new(Orientation::Horizontal, 12);
+    let range_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .css_classes(["linked"])
+        .build();
+    toolbar.append(&range_box);
+
+    let interval_dropdown = DropDown::from_strings(&INTERVALS);
+    interval_dropdown.set_tooltip_text(Some("Interval"));
+    toolbar.append(&interval_dropdown);
+
+    let mode_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .halign(Align::End)
+        .hexpand(true)
+        .css_classes(["linked"])
+        .build();
+    toolbar.append(&mode_box);
+    layout.append(&toolbar);
+
+    let chart = PriceChart::new();
+    layout.append(chart.widget());
+
+    let stats = Grid::builder().column_spacing(24).row_spacing(6).build();
+    layout.append(&stats);
+
+    // Index into RANGES of the selected range
+    let range_index = Rc::new(Cell::new(0));
+
+    let load = glib::clone!(
+        #[strong]
+        chart,
+        #[weak]
+        stats,
+        #[weak]
+        stock,
+        #[weak]
+        interval_dropdown,
+        #[strong]
+        range_index,
+        move || {
+            let (_, range, _) = RANGES[range_index.get()];
+            let interval = INTERVALS[interval_dropdown.selected() as usize];
+            let intraday = !interval.ends_with('d') && !interval.ends_with("wk");
+            let ticker = stock.ticker();
+
+            chart.set_message("Loading…");
+            // Let the window redraw before the fetch blocks the main loop
+            glib::MainContext::default().spawn_local(glib::clone!(
+                #[strong]
+                chart,
+                #[strong]
+                api,
+                async move {
+                    let rt = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .unwrap();
+                    // Same as the list updates, the API needs a tokio runtime of its own
+                    match rt.block_on(api.history(&ticker, interval, range)) {
+                        Ok(history) => {
+                            // Only fetched for the list while its column is shown
+                            if stock.market_cap() <= 0.0
+                                && let Ok(market_cap) = rt.block_on(api.market_cap(&ticker))
+                            {
+                                stock.set_market_cap(market_cap);
+                            }
+                            fill_stats(&stats, &history, stock.market_cap());
+                            chart.set_candles(history.candles, intraday);
+                        }
+                        // Yahoo limits how far back the short intervals go
+                        Err(e) => chart.set_message(&format!("Failed to load prices: {}", e)),
+                    }
+                }
+            ));
+        }
+    );
+
+    interval_dropdown.connect_selected_notify(glib::clone!(
+        #[strong]
+        load,
+        move |_| load()
+    ));
+
+    let mut first_button: Option<ToggleButton> = None;
+    for (index, (label, _, interval)) in RANGES.into_iter().enumerate() {
+        let button = ToggleButton::with_label(label);
+        button.set_group(first_button.as_ref());
+        button.connect_toggled(glib::clone!(
+            #[weak]
+            interval_dropdown,
+            #[strong]
+            range_index,
+            #[strong]
+            load,
+            move |button| {
+                if !button.is_active() {
+                    return;
+                }
+                range_index.set(index);
+                let interval = INTERVALS.iter().position(|i| *i == interval).unwrap() as u32;
+                if interval_dropdown.selected() == interval {
+                    load();
+                } else {
+                    // Loads through the notify handler
+                    interval_dropdown.set_selected(interval);
+                }
+            }
+        ));
+        range_box.append(&button);
+        first_button.get_or_insert(button);
+    }
+
+    let line_button = ToggleButton::builder().label("Line").active(true).build();
+    let candles_button = ToggleButton::builder()
+        .label("Candles")
+        .group(&line_button)
+        .build();
+    candles_button.connect_toggled(glib::clone!(
+        #[strong]
+        chart,
+        move |button| {
+            chart.set_mode(if button.is_active() {
+                ChartMode::Candles
+            } else {
+                ChartMode::Line
+            })
+        }
+    ));
+    mode_box.append(&line_button);
+    mode_box.append(&candles_button);
+
+    let window = Window::builder()
+        .title(stock.ticker())
+        .default_width(640)
+        .default_height(520)
+        .child(&layout)
+        .build();
+    window.set_transient_for(parent);
+    window.present();
+
+    if let Some(button) = first_button {
+        button.set_active(true);
+    }
+}
+
+/// Replaces the contents of `stats` with the key stats from `history`, the market cap is
+/// left out when it's unknown, e.g. for ETFs
+fn fill_stats(stats: &Grid, history: &History, market_cap: f64) {
+    while let Some(child) = stats.first_child() {
+        stats.remove(&child);
+    }
+
+    let range = |low: f64, high: f64| format!("{:.2} – {:.2}", low, high);
+    let mut rows = vec![
+        ("Previous close", format!("{:.2}", history.prev_close)),
+        ("Day range", range(history.day_low, history.day_high)),
+        ("52-week range", range(history.year_low, history.year_high)),
+        ("Volume", format_compact(history.volume)),
+    ];
+    if market_cap > 0.0 {
+        rows.push(("Market cap", format_compact(market_cap)));
+    }
+    rows.push(("Exchange", history.exchange.clone()));
+    rows.push(("Currency", history.currency.clone()));
+
+    // Two columns of label/value pairs
+    let half = rows.len().div_ceil(2);
+    for (i, (title, value)) in rows.into_iter().enumerate() {
+        let column = (i / half) as i32 * 2;
+        let row = (i % half) as i32;
+        stats.attach(
+            &Label::builder()
+                .label(title)
+                .xalign(0.0)
+                .css_classes(["dim-label"])
+                .build(),
+            column,
+            row,
+            1,
+            1,
+        );
+        stats.attach(
+            &Label::builder()
+                .label(value)
+                .xalign(1.0)
+                .css_classes(["numeric"])
+                .build(),
+            column + 1,
+            row,
+            1,
+            1,
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::stock_object::StockObject;
+use gtk::{
+    Align, Box, Button, Entry, Grid, Label, Orientation, ScrolledWindow, TextView, Window,
+    WrapMode, glib, prelude::*,
+};
+
+/// Edits the display name, tags and notes of `stock`, `on_save` runs after the changes
+/// have been applied to the object
+pub fn show_edit_dialog(
+    parent: Option<&Window>,
+    stock: &StockObject,
+    on_save: impl Fn() + 'static,
+) {
+    let grid = Grid::builder()
+        .column_spacing(12)
+        .row_spacing(6)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let name_entry = Entry::builder()
+        .text(stock.display_name())
+        .placeholder_text(stock.name())
+        .hexpand(true)
+        .build();
+    let tags_entry = Entry::builder()
+        .text(stock.tags().join(", "))
+        .placeholder_text("e.g. tech, long-term")
+        .build();
+    let notes_view = TextView::builder()
+        .wrap_mode(WrapMode::WordChar)
+        .top_margin(6)
+        .bottom_margin(6)
+        .left_margin(6)
+        .right_margin(6)
+        .build();
+    notes_view.buffer().set_text(&stock.notes());
+
+    let label = |text: &str| {
+        Label::builder()
+            .label(text)
+            .xalign(0.0)
+            .valign(Align::Start)
+            .build()
+    };
+    grid.attach(&label("Name"), 0, 0, 1, 1);
+    grid.attach(&name_entry, 1, 0, 1, 1);
+    grid.attach(&label("Tags"), 0, 1, 1, 1);
+    grid.attach(&tags_entry, 1, 1, 1, 1);
+    grid.attach(&label("Notes"), 0, 2, 1, 1);
+    grid.attach(
+        &ScrolledWindow::builder()
+            .child(&notes_view)
+            .min_content_height(120)
+            .vexpand(true)
+            .css_classes(["frame"])
+            .build(),
+        1,
+        2,
+        1,
+        1,
+    );
+
+    let buttons = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(6)
+        .halign(Align::End)
+        .build();
+    let cancel_button = Button::with_label("Cancel");
+    let save_button = Button::builder()
+        .label("Save")
+        .css_classes(["suggested-action"])
+        .build();
+    buttons.append(&cancel_button);
+    buttons.append(&save_button);
+    grid.attach(&buttons, 0, 3, 2, 1);
+
+    let dialog = Window::builder()
+        .title(format!("Edit {}", stock.ticker()))
+        .modal(true)
+        .default_width(360)
+        .child(&grid)
+        .build();
+    dialog.set_transient_for(parent);
+
+    cancel_button.connect_clicked(glib::clone!(
+        #[weak]
+        dialog,
+        move |_| dialog.close()
+    ));
+
+    save_button.connect_clicked(glib::clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        stock,
+        move |_| {
+            let buffer = notes_view.buffer();
+            let notes = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+            let tags: Vec<String> = tags_entry
+                .text()
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect();
+
+            stock.set_display_name(name_entry.text().trim());
+            stock.set_tags(tags);
+            stock.set_notes(notes.trim());
+
+            on_save();
+            dialog.close();
+        }
+    ));
+
+    dialog.present();
+}
@@ -0,0 +1,388 @@
+use crate::{
+    chart::{Colors, ThemeColors, set_color},
+    detail_window::show_detail_window,
+    settings::AppSettings,
+    stock_manager::StockManager,
+    stock_object::StockObject,
+};
+use gtk::{
+    Align, Application, Box, DrawingArea, DropDown, GestureClick, Label, Orientation, Window,
+    cairo::{Context, FontSlant, FontWeight},
+    gio::SimpleAction,
+    glib,
+    prelude::*,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// Ids and titles of what tiles can be sized by
+pub const SIZES: [(&str, &str); 2] = [("value", "Holding Value"), ("market_cap", "Market Cap")];
+
+/// Ids and titles of the change tiles can be coloured by
+pub const COLORS: [(&str, &str); 2] = [("1d", "1d Change"), ("1w", "1w Change")];
+
+/// Change, in percent, at which a tile gets its full colour
+const FULL_COLOR_CHANGE: f64 = 3.0;
+/// Gap between tiles
+const GAP: f64 = 1.0;
+
+/// Registers the `app.heatmap` toggle, which swaps the stock list for the heatmap
+pub fn add_actions(application: &Application, manager: Rc<StockManager>, settings: &AppSettings) {
+    let heatmap_action =
+        SimpleAction::new_stateful("heatmap", None, &settings.show_heatmap().to_variant());
+    heatmap_action.connect_change_state(glib::clone!(
+        #[weak]
+        settings,
+        move |action, state| {
+            let Some(show) = state.and_then(|s| s.get::<bool>()) else {
+                return;
+            };
+            action.set_state(&show.into());
+            settings.set_show_heatmap(show);
+            // Market caps are only fetched while something shows them
+            if show && settings.heatmap_size() == "market_cap" {
+                manager.update_stocks();
+            }
+        }
+    ));
+    application.add_action(&heatmap_action);
+    application.set_accels_for_action("app.heatmap", &["<Control>m"]);
+}
+
+/// A tile per stock of the watchlist as shown, sized by holding value or market cap and
+/// coloured by the daily or weekly change. Clicking a tile opens its chart.
+pub fn create_heatmap(manager: Rc<StockManager>, settings: &AppSettings) -> Box {
+    let container = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(6)
+        .margin_start(12)
+        .margin_end(12)
+        .margin_bottom(12)
+        .build();
+
+    let toolbar = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(6)
+        .halign(Align::End)
+        .build();
+    let size_dropdown = setting_dropdown(settings, "heatmap-size", &SIZES);
+    let color_dropdown = setting_dropdown(settings, "heatmap-color", &COLORS);
+    toolbar.append(&Label::new(Some("Size")));
+    toolbar.append(&size_dropdown);
+    toolbar.append(&Label::new(Some("Colour")));
+    toolbar.append(&color_dropdown);
+    container.append(&toolbar);
+
+    let area = DrawingArea::builder()
+        .hexpand(true)
+        .vexpand(true)
+        .has_tooltip(true)
+        .build();
+    container.append(&area);
+
+    let theme_colors = ThemeColors::new(&container);
+
+    // Where each stock was drawn last, for clicks and tooltips
+    let tiles: Rc<RefCell<Vec<(Rect, StockObject)>>> = Rc::default();
+
+    area.set_draw_func(glib::clone!(
+        #[strong]
+        manager,
+        #[weak]
+        settings,
+        #[strong]
+        tiles,
+        move |area, cr, width, height| {
+            let stocks: Vec<StockObject> = manager
+                .sorted_stocks()
+                .iter::<StockObject>()
+                .filter_map(Result::ok)
+                .collect();
+            let layout = layout_tiles(
+                stocks,
+                &settings.heatmap_size(),
+                Rect {
+                    x: 0.0,
+                    y: 0.0,
+                    width: width as f64,
+                    height: height as f64,
+                },
+            );
+            draw(
+                cr,
+                &layout,
+                &settings.heatmap_color(),
+                &theme_colors.colors(area),
+            );
+            tiles.replace(layout);
+        }
+    ));
+
+    area.connect_query_tooltip(glib::clone!(
+        #[strong]
+        tiles,
+        move |_, x, y, _, tooltip| {
+            let tiles = tiles.borrow();
+            let Some((_, stock)) = tile_at(&tiles, x as f64, y as f64) else {
+                return false;
+            };
+            tooltip.set_text(Some(&format!(
+                "{} ({})\n{:.2}  1d {:+.2}%  1w {:+.2}%",
+                stock.title(),
+                stock.ticker(),
+                stock.price(),
+                stock.pct_change_1d() * 100.0,
+                stock.pct_change_1w() * 100.0
+            )));
+            true
+        }
+    ));
+
+    let click = GestureClick::new();
+    click.connect_released(glib::clone!(
+        #[strong]
+        manager,
+        #[strong]
+        tiles,
+        #[weak]
+        area,
+        move |_, _, x, y| {
+            let stock = tile_at(&tiles.borrow(), x, y).map(|(_, stock)| stock.clone());
+            if let Some(stock) = stock {
+                let parent = area.root().and_downcast::<Window>();
+                show_detail_window(parent.as_ref(), &stock, manager.api());
+            }
+        }
+    ));
+    area.add_controller(click);
+
+    // Redraw on new prices, on filtering and adding or removing, and on new settings
+    manager.connect_updated(glib::clone!(
+        #[weak]
+        area,
+        move || area.queue_draw()
+    ));
+    manager.sorted_stocks().connect_items_changed(glib::clone!(
+        #[weak]
+        area,
+        move |_, _, _, _| area.queue_draw()
+    ));
+    settings.connect_notify_local(
+        Some("heatmap-color"),
+        glib::clone!(
+            #[weak]
+            area,
+            move |_, _| area.queue_draw()
+        ),
+    );
+    settings.connect_notify_local(
+        Some("heatmap-size"),
+        glib::clone!(
+            #[weak]
+            area,
+            #[strong]
+            manager,
+            move |settings, _| {
+                area.queue_draw();
+                if settings.heatmap_size() == "market_cap" {
+                    manager.update_stocks();
+                }
+            }
+        ),
+    );
+
+    container
+}
+
+/// A dropdown over `options` that reads and writes the string setting `property`
+fn setting_dropdown(
+    settings: &AppSettings,
+    property: &str,
+    options: &'static [(&str, &str)],
+) -> DropDown {
+    let dropdown =
+        DropDown::from_strings(&options.iter().map(|(_, title)| *title).collect::<Vec<_>>());
+    let current = settings.property::<String>(property);
+    let selected = options
+        .iter()
+        .position(|(id, _)| *id == current)
+        .unwrap_or(0);
+    dropdown.set_selected(selected as u32);
+
+    let property = property.to_string();
+    dropdown.connect_selected_notify(glib::clone!(
+        #[weak]
+        settings,
+        move |dropdown| {
+            if let Some((id, _)) = options.get(dropdown.selected() as usize) {
+                settings.set_property(&property, *id);
+            }
+        }
+    ));
+    dropdown
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Rect {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+fn tile_at(tiles: &[(Rect, StockObject)], x: f64, y: f64) -> Option<&(Rect, StockObject)> {
+    tiles.iter().find(|(rect, _)| rect.contains(x, y))
+}
+
+/// Places `stocks` in `bounds`, largest first. Stocks without a size are left out, unless
+/// none has one, e.g. when nothing is held, then every tile is the same size.
+fn layout_tiles(stocks: Vec<StockObject>, size: &str, bounds: Rect) -> Vec<(Rect, StockObject)> {
+    let weight = |stock: &StockObject| match size {
+        "market_cap" => stock.market_cap(),
+        _ => stock.value(),
+    };
+    let mut weighted: Vec<(f64, StockObject)> = stocks
+        .into_iter()
+        .map(|stock| (weight(&stock).max(0.0), stock))
+        .collect();
+    if weighted.iter().all(|(weight, _)| *weight == 0.0) {
+        weighted.iter_mut().for_each(|(weight, _)| *weight = 1.0);
+    }
+    weighted.retain(|(weight, _)| *weight > 0.0);
+    weighted.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    let weights: Vec<f64> = weighted.iter().map(|(weight, _)| *weight).collect();
+    squarify(&weights, bounds)
+        .into_iter()
+        .zip(weighted.into_iter().map(|(_, stock)| stock))
+        .collect()
+}
+
+/// Splits `bounds` into one rectangle per weight, with areas in proportion to the weights
+/// and as close to square as the squarified treemap layout gets them. `weights` must be
+/// sorted largest first, the rectangles come back in the same order.
+fn squarify(weights: &[f64], bounds: Rect) -> Vec<Rect> {
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 || bounds.width <= 0.0 || bounds.height <= 0.0 {
+        return vec![];
+    }
+    let scale = bounds.width * bounds.height / total;
+    let areas: Vec<f64> = weights.iter().map(|weight| weight * scale).collect();
+
+    let mut rects = Vec::with_capacity(areas.len());
+    let mut free = bounds;
+    let mut start = 0;
+    while start < areas.len() {
+        // Rows run along the shorter side and grow while that makes their tiles squarer
+        let side = free.width.min(free.height);
+        let mut end = start + 1;
+        while end < areas.len()
+            && worst_ratio(&areas[start..=end], side) <= worst_ratio(&areas[start..end], side)
+        {
+            end += 1;
+        }
+
+        let row = &areas[start..end];
+        let row_area: f64 = row.iter().sum();
+        if free.width >= free.height {
+            let row_width = row_area / free.height;
+            let mut y = free.y;
+            for area in row {
+                let height = area / row_width;
+                rects.push(Rect {
+                    x: free.x,
+                    y,
+                    width: row_width,
+                    height,
+                });
+                y += height;
+            }
+            free.x += row_width;
+            free.width -= row_width;
+        } else {
+            let row_height = row_area / free.width;
+            let mut x = free.x;
+            for area in row {
+                let width = area / row_height;
+                rects.push(Rect {
+                    x,
+                    y: free.y,
+                    width,
+                    height: row_height,
+                });
+                x += width;
+            }
+            free.y += row_height;
+            free.height -= row_height;
+        }
+        start = end;
+    }
+    rects
+}
+
+/// The most elongated aspect ratio among `areas` laid out in a row along `side`
+fn worst_ratio(areas: &[f64], side: f64) -> f64 {
+    let sum: f64 = areas.iter().sum();
+    let max = areas.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let min = areas.iter().copied().fold(f64::INFINITY, f64::min);
+    let side = side * side;
+    let sum = sum * sum;
+    (side * max / sum).max(sum / (side * min))
+}
+
+fn draw(cr: &Context, tiles: &[(Rect, StockObject)], color_by: &str, colors: &Colors) {
+    cr.select_font_face("Sans", FontSlant::Normal, FontWeight::Bold);
+    cr.set_font_size(11.0);
+
+    for (rect, stock) in tiles {
+        let change = match color_by {
+            "1w" => stock.pct_change_1w(),
+            _ => stock.pct_change_1d(),
+        } * 100.0;
+
+        // Flat is a faint tint, the colour deepens up to FULL_COLOR_CHANGE
+        let strength = (change.abs() / FULL_COLOR_CHANGE).min(1.0);
+        let color = if change >= 0.0 {
+            &colors.rising
+        } else {
+            &colors.falling
+        };
+        set_color(cr, color, 0.15 + 0.65 * strength);
+        cr.rectangle(
+            rect.x + GAP,
+            rect.y + GAP,
+            (rect.width - 2.0 * GAP).max(0.0),
+            (rect.height - 2.0 * GAP).max(0.0),
+        );
+        let _ = cr.fill();
+
+        // Labels only where they fit, the tooltip has them for the rest
+        let ticker = stock.ticker();
+        let change_text = format!("{:+.2}%", change);
+        let (Ok(ticker_extents), Ok(change_extents)) =
+            (cr.text_extents(&ticker), cr.text_extents(&change_text))
+        else {
+            continue;
+        };
+        let text_width = ticker_extents.width().max(change_extents.width());
+        if text_width + 8.0 > rect.width || rect.height < 32.0 {
+            continue;
+        }
+        let center_x = rect.x + rect.width / 2.0;
+        let center_y = rect.y + rect.height / 2.0;
+        set_color(cr, &colors.foreground, 1.0);
+        cr.move_to(center_x - ticker_extents.width() / 2.0, center_y - 2.0);
+        let _ = cr.show_text(&ticker);
+        set_color(cr, &colors.foreground, 0.8);
+        cr.move_to(
+            center_x - change_extents.width() / 2.0,
+            center_y + change_extents.height() + 2.0,
+        );
+        let _ = cr.show_text(&change_text);
+    }
+}
@@ -0,0 +1,510 @@
+use crate::{
+    broker_import::{
+        MappingProfile, Trade, load_profiles, parse_mapped_csv, parse_ofx, read_headers,
+        resolve_holdings, save_profile, trades_to_holdings,
+    },
+    csv_io::{CsvImport, read_csv, write_csv},
+    persistence::ImportEntry,
+    stock_manager::StockManager,
+};
+use gtk::{
+    AlertDialog, Align, Application, ApplicationWindow, Box, Button, DropDown, Entry, FileDialog,
+    FileFilter, Grid, INVALID_LIST_POSITION, Label, Orientation, ScrolledWindow, Window,
+    gio::{self, SimpleAction},
+    glib,
+    pango::EllipsizeMode,
+    prelude::*,
+};
+use std::{collections::HashSet, fs::read_to_string, path::Path, rc::Rc};
+
+/// Registers the `app.import-csv`, `app.export-csv` and `app.import-statement` actions
+pub fn add_actions(
+    application: &Application,
+    window: &ApplicationWindow,
+    manager: Rc<StockManager>,
+) {
+    let import_action = SimpleAction::new("import-csv", None);
+    import_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        #[strong]
+        manager,
+        move |_, _| {
+            let dialog = csv_file_dialog("Import CSV");
+            dialog.open(
+                Some(&window),
+                gio::Cancellable::NONE,
+                glib::clone!(
+                    #[weak]
+                    window,
+                    #[strong]
+                    manager,
+                    move |result| {
+                        let Some(path) = result.ok().and_then(|file| file.path()) else {
+                            return;
+                        };
+                        match read_csv(&path) {
+                            Ok(import) => show_import_preview(&window, import, manager),
+                            Err(e) => show_error(&window, "Import failed", &e.to_string()),
+                        }
+                    }
+                ),
+            );
+        }
+    ));
+    application.add_action(&import_action);
+
+    let export_action = SimpleAction::new("export-csv", None);
+    export_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        #[strong]
+        manager,
+        move |_, _| {
+            let dialog = csv_file_dialog("Export CSV");
+            dialog.set_initial_name(Some("stockfin.csv"));
+            dialog.save(
+                Some(&window),
+                gio::Cancellable::NONE,
+                glib::clone!(
+                    #[weak]
+                    window,
+                    #[strong]
+                    manager,
+                    move |result| {
+                        let Some(path) = result.ok().and_then(|file| file.path()) else {
+                            return;
+                        };
+                        if let Err(e) = write_csv(&path, &manager.entries()) {
+                            show_error(&window, "Export failed", &e.to_string());
+                        }
+                    }
+                ),
+            );
+        }
+    ));
+    application.add_action(&export_action);
+
+    let statement_action = SimpleAction::new("import-statement", None);
+    statement_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        #[strong]
+        manager,
+        move |_, _| {
+            let dialog = file_dialog(
+                "Import Broker Statement",
+                "Broker statements",
+                &["csv", "ofx", "qfx"],
+            );
+            dialog.open(
+                Some(&window),
+                gio::Cancellable::NONE,
+                glib::clone!(
+                    #[weak]
+                    window,
+                    #[strong]
+                    manager,
+                    move |result| {
+                        let Some(path) = result.ok().and_then(|file| file.path()) else {
+                            return;
+                        };
+                        import_statement(&window, &path, manager);
+                    }
+                ),
+            );
+        }
+    ));
+    application.add_action(&statement_action);
+}
+
+fn csv_file_dialog(title: &str) -> FileDialog {
+    file_dialog(title, "CSV files", &["csv"])
+}
+
+fn file_dialog(title: &str, filter_name: &str, suffixes: &[&str]) -> FileDialog {
+    let filter = FileFilter::new();
+    filter.set_name(Some(filter_name));
+    for suffix in suffixes {
+        filter.add_suffix(suffix);
+    }
+
+    let filters = gio::ListStore::new::<FileFilter>();
+    filters.append(&filter);
+
+    FileDialog::builder()
+        .title(title)
+        .modal(true)
+        .filters(&filters)
+        .default_filter(&filter)
+        .build()
+}
+
+/// OFX/QFX files are imported directly, CSV files first need a column mapping
+fn import_statement(window: &ApplicationWindow, path: &Path, manager: Rc<StockManager>) {
+    let data = match read_to_string(path) {
+        Ok(data) => data,
+        Err(e) => return show_error(window, "Import failed", &e.to_string()),
+    };
+
+    if data.contains("<OFX>") {
+        match parse_ofx(&data) {
+            Ok(trades) => resolve_and_preview(window, trades, vec![], manager),
+            Err(e) => show_error(window, "Import failed", &e.to_string()),
+        }
+        return;
+    }
+
+    match read_headers(&data) {
+        Ok((headers, _)) => show_mapping_window(window, data, headers, manager),
+        Err(e) => show_error(window, "Import failed", &e.to_string()),
+    }
+}
+
+/// Looks up a Yahoo ticker for every holding before showing the regular import preview
+fn resolve_and_preview(
+    window: &ApplicationWindow,
+    trades: Vec<Trade>,
+    mut errors: Vec<String>,
+    manager: Rc<StockManager>,
+) {
+    let holdings = trades_to_holdings(&trades);
+    let api = manager.api();
+
+    glib::MainContext::default().spawn_local(glib::clone!(
+        #[weak]
+        window,
+        async move {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+
+            let mut import = rt.block_on(resolve_holdings(&api, &holdings));
+            errors.append(&mut import.errors);
+            import.errors = errors;
+
+            show_import_preview(&window, import, manager);
+        }
+    ));
+}
+
+/// Lets the user pick which broker CSV columns hold the symbol, quantity, price and so on.
+/// Saved profiles are preselected when their columns match the file.
+fn show_mapping_window(
+    parent: &ApplicationWindow,
+    data: String,
+    headers: Vec<String>,
+    manager: Rc<StockManager>,
+) {
+    const NONE: &str = "(none)";
+
+    let profile = load_profiles()
+        .into_iter()
+        .find(|p| p.matches(&headers))
+        .unwrap_or_default();
+
+    let grid = Grid::builder()
+        .column_spacing(12)
+        .row_spacing(6)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let header_strs: Vec<&str> = headers.iter().map(String::as_str).collect();
+    let optional_strs: Vec<&str> = std::iter::once(NONE).chain(header_strs.clone()).collect();
+
+    let column_dropdown = |row: i32, title: &str, selected: Option<&str>, optional: bool| {
+        let strings = if optional {
+            &optional_strs
+        } else {
+            &header_strs
+        };
+        let dropdown = DropDown::from_strings(strings);
+        let position = selected
+            .and_then(|col| strings.iter().position(|s| s.eq_ignore_ascii_case(col)))
+            .unwrap_or(0);
+        dropdown.set_selected(position as u32);
+        dropdown.set_hexpand(true);
+
+        grid.attach(
+            &Label::builder().label(title).xalign(0.0).build(),
+            0,
+            row,
+            1,
+            1,
+        );
+        grid.attach(&dropdown, 1, row, 1, 1);
+        dropdown
+    };
+
+    let symbol = column_dropdown(0, "Symbol", Some(&profile.symbol_column), false);
+    let name = column_dropdown(1, "Name", profile.name_column.as_deref(), true);
+    let quantity = column_dropdown(2, "Quantity", Some(&profile.quantity_column), false);
+    let price = column_dropdown(3, "Price", Some(&profile.price_column), false);
+    let action = column_dropdown(4, "Action", profile.action_column.as_deref(), true);
+
+    let text_entry = |row: i32, title: &str, text: &str, placeholder: &str| {
+        let entry = Entry::builder()
+            .text(text)
+            .placeholder_text(placeholder)
+            .build();
+        grid.attach(
+            &Label::builder().label(title).xalign(0.0).build(),
+            0,
+            row,
+            1,
+            1,
+        );
+        grid.attach(&entry, 1, row, 1, 1);
+        entry
+    };
+
+    let buy_values = text_entry(
+        5,
+        "Buy values",
+        &profile.buy_values.join(", "),
+        "e.g. Buy, Bought",
+    );
+    let sell_values = text_entry(
+        6,
+        "Sell values",
+        &profile.sell_values.join(", "),
+        "e.g. Sell, Sold",
+    );
+    let profile_name = text_entry(
+        7,
+        "Save profile as",
+        &profile.name,
+        "Leave empty to not save",
+    );
+
+    let buttons = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(6)
+        .halign(Align::End)
+        .build();
+    let cancel_button = Button::with_label("Cancel");
+    let continue_button = Button::builder()
+        .label("Continue")
+        .css_classes(["suggested-action"])
+        .build();
+    buttons.append(&cancel_button);
+    buttons.append(&continue_button);
+    grid.attach(&buttons, 0, 8, 2, 1);
+
+    let mapping = Window::builder()
+        .title("Map Statement Columns")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(400)
+        .child(&grid)
+        .build();
+
+    cancel_button.connect_clicked(glib::clone!(
+        #[weak]
+        mapping,
+        move |_| mapping.close()
+    ));
+
+    continue_button.connect_clicked(glib::clone!(
+        #[weak]
+        mapping,
+        #[weak]
+        parent,
+        move |_| {
+            let selected_column = |dropdown: &DropDown, optional: bool| {
+                let position = dropdown.selected();
+                if position == INVALID_LIST_POSITION || (optional && position == 0) {
+                    return None;
+                }
+                let index = position as usize - usize::from(optional);
+                headers.get(index).cloned()
+            };
+            let split_values = |entry: &Entry| {
+                entry
+                    .text()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(String::from)
+                    .collect()
+            };
+
+            let profile = MappingProfile {
+                name: profile_name.text().trim().to_string(),
+                symbol_column: selected_column(&symbol, false).unwrap_or_default(),
+                quantity_column: selected_column(&quantity, false).unwrap_or_default(),
+                price_column: selected_column(&price, false).unwrap_or_default(),
+                name_column: selected_column(&name, true),
+                action_column: selected_column(&action, true),
+                buy_values: split_values(&buy_values),
+                sell_values: split_values(&sell_values),
+            };
+
+            match parse_mapped_csv(&data, &profile) {
+                Ok((trades, errors)) => {
+                    if !profile.name.is_empty() {
+                        save_profile(profile);
+                    }
+                    mapping.close();
+                    resolve_and_preview(&parent, trades, errors, manager.clone());
+                }
+                Err(e) => show_error(&mapping, "Import failed", &e.to_string()),
+            }
+        }
+    ));
+
+    mapping.present();
+}
+
+pub fn show_error(parent: &impl IsA<Window>, message: &str, detail: &str) {
+    AlertDialog::builder()
+        .message(message)
+        .detail(detail)
+        .modal(true)
+        .build()
+        .show(Some(parent));
+}
+
+/// Lists the parsed rows and any validation errors, nothing is imported until confirmed
+fn show_import_preview(parent: &ApplicationWindow, import: CsvImport, manager: Rc<StockManager>) {
+    let existing: HashSet<String> = manager.entries().into_iter().map(|e| e.ticker).collect();
+    let new_count = import
+        .entries
+        .iter()
+        .filter(|e| !existing.contains(&e.ticker))
+        .count();
+
+    let layout = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let summary = Label::builder()
+        .label(format!(
+            "{} new, {} updated, {} skipped",
+            new_count,
+            import.entries.len() - new_count,
+            import.errors.len()
+        ))
+        .halign(Align::Start)
+        .build();
+    layout.append(&summary);
+
+    let grid = Grid::builder().column_spacing(12).row_spacing(4).build();
+    for (col, title) in ["Ticker", "Name", "Quantity", "Cost", "Tags", ""]
+        .iter()
+        .enumerate()
+    {
+        let label = Label::builder()
+            .label(format!("<b>{}</b>", title))
+            .use_markup(true)
+            .halign(Align::Start)
+            .build();
+        grid.attach(&label, col as i32, 0, 1, 1);
+    }
+    for (row, entry) in import.entries.iter().enumerate() {
+        let update = existing.contains(&entry.ticker);
+        // Updates keep whatever the import doesn't have, new tickers start out empty
+        let cells = if update {
+            [
+                Some(entry.ticker.clone()),
+                (!entry.name.is_empty()).then(|| entry.name.clone()),
+                entry.quantity.map(|quantity| format!("{}", quantity)),
+                entry.cost.map(|cost| format!("{:.2}", cost)),
+                entry.tags.as_ref().map(|tags| tags.join(", ")),
+                Some("Update".to_string()),
+            ]
+        } else {
+            let new = entry.to_entry();
+            [
+                Some(new.ticker),
+                Some(new.name),
+                Some(format!("{}", new.quantity)),
+                Some(format!("{:.2}", new.cost)),
+                Some(new.tags.join(", ")),
+                Some("New".to_string()),
+            ]
+        };
+        for (col, text) in cells.iter().enumerate() {
+            let label = Label::builder()
+                .label(text.as_deref().unwrap_or("unchanged"))
+                .halign(Align::Start)
+                .ellipsize(EllipsizeMode::End)
+                .max_width_chars(24)
+                .build();
+            if text.is_none() {
+                label.add_css_class("dim-label");
+            }
+            grid.attach(&label, col as i32, row as i32 + 1, 1, 1);
+        }
+    }
+
+    let rows = Box::new(Orientation::Vertical, 12);
+    rows.append(&grid);
+    for error in &import.errors {
+        let label = Label::builder()
+            .label(error)
+            .halign(Align::Start)
+            .wrap(true)
+            .css_classes(["error"])
+            .build();
+        rows.append(&label);
+    }
+
+    let scrolled = ScrolledWindow::builder()
+        .child(&rows)
+        .vexpand(true)
+        .min_content_height(200)
+        .build();
+    layout.append(&scrolled);
+
+    let buttons = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(6)
+        .halign(Align::End)
+        .build();
+    let cancel_button = Button::with_label("Cancel");
+    let import_button = Button::builder()
+        .label("Import")
+        .css_classes(["suggested-action"])
+        .sensitive(!import.entries.is_empty())
+        .build();
+    buttons.append(&cancel_button);
+    buttons.append(&import_button);
+    layout.append(&buttons);
+
+    let preview = Window::builder()
+        .title("Import Preview")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(560)
+        .default_height(400)
+        .child(&layout)
+        .build();
+
+    cancel_button.connect_clicked(glib::clone!(
+        #[weak]
+        preview,
+        move |_| preview.close()
+    ));
+
+    let entries: Vec<ImportEntry> = import.entries;
+    import_button.connect_clicked(glib::clone!(
+        #[weak]
+        preview,
+        move |_| {
+            manager.import_entries(&entries);
+            preview.close();
+        }
+    ));
+
+    preview.present();
+}
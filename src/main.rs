@@ -1,21 +1,55 @@
+pub mod backups;
+pub mod broker_import;
+pub mod chart;
+pub mod cli;
+pub mod compare_window;
+pub mod csv_io;
 pub mod dbus;
+pub mod detail_window;
+pub mod edit_dialog;
+pub mod heatmap;
+pub mod import_export;
 pub mod persistence;
+pub mod preferences;
+pub mod quick_filter;
+pub mod settings;
+pub mod shortcuts;
+pub mod sparkline;
 pub mod stock_api;
 pub mod stock_manager;
 pub mod stock_object;
+pub mod summary;
+pub mod theme;
+pub mod ticker_tape;
+pub mod toast;
+pub mod validate;
 
-use crate::{dbus::StockfinBus, persistence::load_tickers, stock_manager::StockManager};
+use crate::{
+    dbus::{StockfinBus, StockfinBusState},
+    persistence::load_tickers,
+    settings::AppSettings,
+    stock_manager::StockManager,
+};
 use gtk::{
-    Application, ApplicationWindow, Box, CssProvider, Orientation,
-    STYLE_PROVIDER_PRIORITY_APPLICATION,
+    Application, ApplicationWindow, Box, Orientation, Overlay, Stack,
     gdk::Display,
-    glib::{ControlFlow, Propagation, timeout_add_local},
+    gio::Menu,
+    glib::{self, ControlFlow, Propagation, SourceId, timeout_add_local},
     prelude::*,
-    style_context_add_provider_for_display,
 };
-use std::{rc::Rc, time::Duration};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
+};
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     let application = Application::builder()
         .application_id("org.jlodenius.stockfin")
         .build();
@@ -26,14 +60,7 @@ fn main() {
 }
 
 fn on_startup(_app: &Application) {
-    let css_provider = CssProvider::new();
-    css_provider.load_from_data(include_str!("../resources/style.css"));
-
-    style_context_add_provider_for_display(
-        &Display::default().expect("Could not connect to a display."),
-        &css_provider,
-        STYLE_PROVIDER_PRIORITY_APPLICATION,
-    );
+    theme::load_base_style(&Display::default().expect("Could not connect to a display."));
 }
 
 fn on_activate(application: &Application) {
@@ -42,9 +69,26 @@ fn on_activate(application: &Application) {
         return window.present();
     }
 
+    let settings = AppSettings::load();
     let tickers = load_tickers();
     let bus_state = StockfinBus::spawn();
-    let stock_manager = Rc::new(StockManager::new(&tickers, bus_state));
+
+    sync_thresholds(&settings, &bus_state);
+    settings.connect_notify_local(
+        None,
+        glib::clone!(
+            #[strong]
+            bus_state,
+            move |settings, _| sync_thresholds(settings, &bus_state)
+        ),
+    );
+
+    if let Some(display) = Display::default() {
+        theme::apply(&display, &settings);
+    }
+
+    let stock_manager = Rc::new(StockManager::new(&tickers, bus_state, settings.clone()));
+    stock_manager.watch_config();
 
     let main_layout = Box::builder()
         .orientation(Orientation::Vertical)
@@ -53,30 +97,143 @@ fn on_activate(application: &Application) {
 
     let stock_list = stock_manager.create_stock_list();
     stock_list.set_vexpand(true);
-    main_layout.append(&stock_manager.create_search_bar());
-    main_layout.append(&stock_list);
+    let header = Box::new(Orientation::Horizontal, 6);
+    let (search_bar, search_entry) = stock_manager.create_search_bar();
+    search_bar.set_hexpand(true);
+    header.append(&search_bar);
+    let tag_filter = stock_manager.create_tag_filter();
+    header.append(&tag_filter);
+    header.append(&stock_manager.create_refresh_controls());
+    main_layout.append(&header);
+    let quick_filter = stock_manager.create_quick_filter();
+    main_layout.append(&quick_filter);
+    main_layout.append(&stock_manager.create_summary_bar());
+
+    // The list and the heatmap show the same stocks, the View menu switches between them
+    let views = Stack::builder().vexpand(true).build();
+    views.add_named(&stock_list, Some("list"));
+    views.add_named(
+        &heatmap::create_heatmap(stock_manager.clone(), &settings),
+        Some("heatmap"),
+    );
+    settings
+        .bind_property("show-heatmap", &views, "visible-child-name")
+        .transform_to(|_, show: bool| Some(if show { "heatmap" } else { "list" }))
+        .sync_create()
+        .build();
+    main_layout.append(&views);
+
+    let overlay = Overlay::builder().child(&main_layout).build();
+    overlay.add_overlay(stock_manager.toast().widget());
 
     let window = ApplicationWindow::builder()
         .application(application)
         .title("Stockfin")
-        .default_width(400)
-        .default_height(400)
-        .child(&main_layout)
+        .child(&overlay)
         .show_menubar(true)
         .build();
 
-    window.connect_close_request(move |w| {
-        w.hide();
-        Propagation::Stop // Prevent window from being destroyed
-    });
+    settings
+        .bind_property("window-width", &window, "default-width")
+        .sync_create()
+        .build();
+    settings
+        .bind_property("window-height", &window, "default-height")
+        .sync_create()
+        .build();
+
+    import_export::add_actions(application, &window, stock_manager.clone());
+    backups::add_actions(application, &window, stock_manager.clone());
+    validate::add_actions(application, &window, stock_manager.clone());
+    ticker_tape::add_actions(application, &window, stock_manager.clone());
+    heatmap::add_actions(application, stock_manager.clone(), &settings);
+    preferences::add_actions(application, &window, &settings);
+    shortcuts::add_actions(
+        application,
+        &window,
+        stock_manager.clone(),
+        &search_entry,
+        &quick_filter,
+        &tag_filter,
+    );
+    application.set_menubar(Some(&build_menubar()));
+
+    window.connect_close_request(glib::clone!(
+        #[weak]
+        settings,
+        #[strong]
+        stock_manager,
+        #[upgrade_or]
+        Propagation::Proceed,
+        move |w| {
+            // Commits a pending removal instead of leaving it waiting for an undo
+            stock_manager.toast().hide();
+
+            // Remember the size for the next launch
+            let (width, height) = w.default_size();
+            settings.set_window_width(width);
+            settings.set_window_height(height);
+
+            w.set_visible(false);
+            Propagation::Stop // Prevent window from being destroyed
+        }
+    ));
     window.present();
 
-    // Update prices once every 60 seconds
-    let manager_clone = stock_manager.clone();
-    timeout_add_local(Duration::from_secs(60), move || {
+    // Update prices on the configured interval, restarting the timer when it changes
+    let refresh_source = Rc::new(RefCell::new(None));
+    schedule_refresh(&stock_manager, settings.refresh_interval(), &refresh_source);
+    settings.connect_notify_local(Some("refresh-interval"), move |settings, _| {
+        schedule_refresh(&stock_manager, settings.refresh_interval(), &refresh_source);
+    });
+}
+
+fn schedule_refresh(manager: &Rc<StockManager>, interval: u32, source: &RefCell<Option<SourceId>>) {
+    if let Some(source) = source.take() {
+        source.remove();
+    }
+
+    let manager_clone = manager.clone();
+    let new_source = timeout_add_local(Duration::from_secs(interval.into()), move || {
         manager_clone.update_stocks();
 
         // Continue = keep timer running
         ControlFlow::Continue
     });
+    source.replace(Some(new_source));
+}
+
+fn sync_thresholds(settings: &AppSettings, bus_state: &Arc<StockfinBusState>) {
+    bus_state
+        .bullish_threshold
+        .store(settings.bullish_threshold(), Ordering::Release);
+    bus_state
+        .bearish_threshold
+        .store(settings.bearish_threshold(), Ordering::Release);
+}
+
+fn build_menubar() -> Menu {
+    let file_menu = Menu::new();
+    file_menu.append(Some("Import CSV…"), Some("app.import-csv"));
+    file_menu.append(Some("Export CSV…"), Some("app.export-csv"));
+    file_menu.append(
+        Some("Import Broker Statement…"),
+        Some("app.import-statement"),
+    );
+    file_menu.append(Some("Restore Backup…"), Some("app.restore-backup"));
+    file_menu.append(Some("Validate Watchlist…"), Some("app.validate-watchlist"));
+    file_menu.append(Some("Preferences"), Some("app.preferences"));
+
+    let view_menu = Menu::new();
+    view_menu.append(Some("Heatmap"), Some("app.heatmap"));
+    view_menu.append(Some("Ticker Tape"), Some("app.ticker-tape"));
+
+    let help_menu = Menu::new();
+    help_menu.append(Some("Keyboard Shortcuts"), Some("app.shortcuts"));
+
+    let menubar = Menu::new();
+    menubar.append_submenu(Some("File"), &file_menu);
+    menubar.append_submenu(Some("View"), &view_menu);
+    menubar.append_submenu(Some("Help"), &help_menu);
+    menubar
 }
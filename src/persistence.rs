@@ -1,30 +1,293 @@
+use anyhow::{Result, anyhow};
 use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
 use std::{
-    fs::{create_dir_all, read_to_string, write},
-    path::PathBuf,
+    cmp::Reverse,
+    fs::{File, create_dir_all, read_dir, read_to_string, rename},
+    io::Write,
+    path::{Path, PathBuf},
+    time::SystemTime,
 };
 
-pub fn get_config_path() -> PathBuf {
+/// Number of previous versions of `tickers.json` kept in the backup directory
+const BACKUP_COUNT: usize = 5;
+
+/// A single saved row of the watchlist
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TickerEntry {
+    pub ticker: String,
+    pub name: String,
+    /// Number of units held, 0 for a plain watchlist entry
+    #[serde(default)]
+    pub quantity: f64,
+    /// Average cost per unit
+    #[serde(default)]
+    pub cost: f64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Shown instead of `name` when set
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl TickerEntry {
+    pub fn new(ticker: &str, name: &str) -> Self {
+        Self {
+            ticker: ticker.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Takes whatever `other` has, the name is only replaced if `other` has one
+    pub fn merge_from(&mut self, other: &ImportEntry) {
+        if !other.name.is_empty() {
+            self.name = other.name.clone();
+        }
+        if let Some(quantity) = other.quantity {
+            self.quantity = quantity;
+        }
+        if let Some(cost) = other.cost {
+            self.cost = cost;
+        }
+        if let Some(tags) = &other.tags {
+            self.tags = tags.clone();
+        }
+    }
+}
+
+/// An imported row. Fields are `None` when the source doesn't have them, e.g. a CSV file
+/// without a `tags` column or a broker statement, so existing tickers keep theirs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportEntry {
+    pub ticker: String,
+    pub name: String,
+    pub quantity: Option<f64>,
+    pub cost: Option<f64>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl ImportEntry {
+    /// The entry for a ticker that isn't on the watchlist yet
+    pub fn to_entry(&self) -> TickerEntry {
+        TickerEntry {
+            ticker: self.ticker.clone(),
+            name: if self.name.is_empty() {
+                self.ticker.clone()
+            } else {
+                self.name.clone()
+            },
+            quantity: self.quantity.unwrap_or_default(),
+            cost: self.cost.unwrap_or_default(),
+            tags: self.tags.clone().unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Merges `incoming` into `entries`, updating existing tickers and appending new ones
+pub fn merge_entries(entries: &mut Vec<TickerEntry>, incoming: &[ImportEntry]) {
+    for new in incoming {
+        match entries.iter_mut().find(|e| e.ticker == new.ticker) {
+            Some(existing) => existing.merge_from(new),
+            None => entries.push(new.to_entry()),
+        }
+    }
+}
+
+pub fn get_config_dir() -> PathBuf {
     let proj_dirs = ProjectDirs::from("org", "jlodenius", "stockfin")
         .expect("Could not determine config directory");
 
     let config_dir = proj_dirs.config_dir();
     create_dir_all(config_dir).ok();
-    config_dir.join("tickers.json")
+    config_dir.to_path_buf()
+}
+
+pub fn get_config_path() -> PathBuf {
+    get_config_dir().join("tickers.json")
+}
+
+fn get_backup_dir() -> PathBuf {
+    let backup_dir = get_config_dir().join("backups");
+    create_dir_all(&backup_dir).ok();
+    backup_dir
 }
 
-pub fn save_tickers(tickers: Vec<(String, String)>) {
+/// Saves the watchlist, rotating the previous file into the backups first
+pub fn save_tickers(tickers: &[TickerEntry]) -> Result<()> {
     let path = get_config_path();
-    if let Ok(json) = serde_json::to_string(&tickers) {
-        write(path, json).ok();
+    let json = serde_json::to_string(tickers)?;
+
+    if path.exists() {
+        rotate_backups(&path)?;
     }
+    write_atomic(&path, json.as_bytes())
 }
 
-pub fn load_tickers() -> Vec<(String, String)> {
+/// Writes to a temporary file and renames it over `path`, so a crash mid-write can
+/// never leave a truncated file behind
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let write_tmp = || -> std::io::Result<()> {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(data)?;
+        file.sync_all()
+    };
+
+    write_tmp().map_err(|e| anyhow!("Could not write {}: {}", tmp_path.display(), e))?;
+    rename(&tmp_path, path).map_err(|e| anyhow!("Could not replace {}: {}", path.display(), e))
+}
+
+/// Shifts `tickers.json.1..N` up by one, dropping the oldest, and copies the current
+/// file into slot 1
+fn rotate_backups(path: &Path) -> Result<()> {
+    let backup_dir = get_backup_dir();
+    let backup_path = |n: usize| backup_dir.join(format!("tickers.json.{}", n));
+
+    for n in (1..BACKUP_COUNT).rev() {
+        if backup_path(n).exists() {
+            rename(backup_path(n), backup_path(n + 1)).ok();
+        }
+    }
+
+    std::fs::copy(path, backup_path(1))
+        .map(|_| ())
+        .map_err(|e| anyhow!("Could not back up {}: {}", path.display(), e))
+}
+
+/// Lists existing backups, newest first
+pub fn list_backups() -> Vec<(PathBuf, SystemTime)> {
+    let mut backups: Vec<_> = read_dir(get_backup_dir())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("tickers.json.")
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((entry.path(), modified))
+        })
+        .collect();
+
+    backups.sort_by_key(|(_, modified)| Reverse(*modified));
+    backups
+}
+
+pub fn read_backup(path: &Path) -> Result<Vec<TickerEntry>> {
+    let data =
+        read_to_string(path).map_err(|e| anyhow!("Could not read {}: {}", path.display(), e))?;
+    parse_tickers(&data).ok_or_else(|| anyhow!("{} is not a valid watchlist", path.display()))
+}
+
+pub fn load_tickers() -> Vec<TickerEntry> {
     let path = get_config_path();
 
     read_to_string(path)
         .ok()
-        .and_then(|data| serde_json::from_str::<Vec<(String, String)>>(&data).ok())
+        .and_then(|data| parse_tickers(&data))
         .unwrap_or_default()
 }
+
+/// Like `load_tickers`, but fails instead of returning an empty list for a broken file
+pub fn read_tickers() -> Result<Vec<TickerEntry>> {
+    let path = get_config_path();
+    let data =
+        read_to_string(&path).map_err(|e| anyhow!("Could not read {}: {}", path.display(), e))?;
+    parse_tickers(&data).ok_or_else(|| anyhow!("{} is not a valid watchlist", path.display()))
+}
+
+/// Parses the watchlist, falling back to the old `[ticker, name]` pair format
+fn parse_tickers(data: &str) -> Option<Vec<TickerEntry>> {
+    serde_json::from_str::<Vec<TickerEntry>>(data)
+        .ok()
+        .or_else(|| {
+            serde_json::from_str::<Vec<(String, String)>>(data)
+                .ok()
+                .map(|pairs| {
+                    pairs
+                        .iter()
+                        .map(|(ticker, name)| TickerEntry::new(ticker, name))
+                        .collect()
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn held(ticker: &str) -> TickerEntry {
+        TickerEntry {
+            quantity: 10.0,
+            cost: 5.0,
+            tags: vec!["tech".to_string()],
+            ..TickerEntry::new(ticker, "Held Inc.")
+        }
+    }
+
+    #[test]
+    fn missing_columns_keep_existing_values() {
+        let mut entries = vec![held("AAPL")];
+        merge_entries(
+            &mut entries,
+            &[ImportEntry {
+                ticker: "AAPL".to_string(),
+                name: "Apple Inc.".to_string(),
+                ..Default::default()
+            }],
+        );
+
+        assert_eq!(
+            entries,
+            vec![TickerEntry {
+                name: "Apple Inc.".to_string(),
+                ..held("AAPL")
+            }]
+        );
+    }
+
+    #[test]
+    fn present_columns_overwrite() {
+        let mut entries = vec![held("AAPL")];
+        merge_entries(
+            &mut entries,
+            &[ImportEntry {
+                ticker: "AAPL".to_string(),
+                quantity: Some(0.0),
+                cost: Some(0.0),
+                tags: Some(vec![]),
+                ..Default::default()
+            }],
+        );
+
+        assert_eq!(entries, vec![TickerEntry::new("AAPL", "Held Inc.")]);
+    }
+
+    #[test]
+    fn appends_new_tickers() {
+        let mut entries = vec![];
+        merge_entries(
+            &mut entries,
+            &[ImportEntry {
+                ticker: "MSFT".to_string(),
+                quantity: Some(2.0),
+                ..Default::default()
+            }],
+        );
+
+        assert_eq!(
+            entries,
+            vec![TickerEntry {
+                quantity: 2.0,
+                ..TickerEntry::new("MSFT", "MSFT")
+            }]
+        );
+    }
+}
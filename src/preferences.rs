@@ -0,0 +1,163 @@
+use crate::{settings::AppSettings, stock_manager::COLUMNS, theme::THEMES};
+use gtk::{
+    Adjustment, Align, Application, ApplicationWindow, Box, CheckButton, DropDown, Grid, Label,
+    Orientation, SpinButton, Window, gio::SimpleAction, glib, prelude::*,
+};
+
+/// Registers the `app.preferences` action
+pub fn add_actions(application: &Application, window: &ApplicationWindow, settings: &AppSettings) {
+    let preferences_action = SimpleAction::new("preferences", None);
+    preferences_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        #[weak]
+        settings,
+        move |_, _| show_preferences(&window, &settings)
+    ));
+    application.add_action(&preferences_action);
+    application.set_accels_for_action("app.preferences", &["<Control>comma"]);
+}
+
+/// Every control is bound straight to the settings, so changes apply and save immediately
+fn show_preferences(parent: &ApplicationWindow, settings: &AppSettings) {
+    let grid = Grid::builder()
+        .column_spacing(12)
+        .row_spacing(6)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let mut row = 0;
+    let mut add_spin = |title: &str, property: &str, adjustment: Adjustment, digits: u32| {
+        let spin = SpinButton::builder()
+            .adjustment(&adjustment)
+            .digits(digits)
+            .hexpand(true)
+            .build();
+        settings
+            .bind_property(property, &adjustment, "value")
+            .bidirectional()
+            .sync_create()
+            .build();
+
+        grid.attach(
+            &Label::builder().label(title).xalign(0.0).build(),
+            0,
+            row,
+            1,
+            1,
+        );
+        grid.attach(&spin, 1, row, 1, 1);
+        row += 1;
+    };
+
+    add_spin(
+        "Refresh interval (seconds)",
+        "refresh-interval",
+        Adjustment::new(60.0, 5.0, 3600.0, 5.0, 60.0, 0.0),
+        0,
+    );
+    add_spin(
+        "Bullish above (%)",
+        "bullish-threshold",
+        Adjustment::new(5.0, 0.0, 100.0, 0.5, 1.0, 0.0),
+        1,
+    );
+    add_spin(
+        "Bearish below (%)",
+        "bearish-threshold",
+        Adjustment::new(-5.0, -100.0, 0.0, 0.5, 1.0, 0.0),
+        1,
+    );
+    add_spin(
+        "Window width",
+        "window-width",
+        Adjustment::new(400.0, 200.0, 4000.0, 10.0, 100.0, 0.0),
+        0,
+    );
+    add_spin(
+        "Window height",
+        "window-height",
+        Adjustment::new(400.0, 200.0, 4000.0, 10.0, 100.0, 0.0),
+        0,
+    );
+
+    let theme_dropdown = DropDown::from_strings(&THEMES.map(|(_, title)| title));
+    let theme = settings.theme();
+    let selected = THEMES.iter().position(|(id, _)| *id == theme).unwrap_or(0);
+    theme_dropdown.set_selected(selected as u32);
+    theme_dropdown.connect_selected_notify(glib::clone!(
+        #[weak]
+        settings,
+        move |dropdown| {
+            if let Some((id, _)) = THEMES.get(dropdown.selected() as usize) {
+                settings.set_theme(*id);
+            }
+        }
+    ));
+    grid.attach(
+        &Label::builder().label("Theme").xalign(0.0).build(),
+        0,
+        row,
+        1,
+        1,
+    );
+    grid.attach(&theme_dropdown, 1, row, 1, 1);
+    row += 1;
+
+    let flash_check = CheckButton::builder()
+        .label("Flash rows when prices change")
+        .build();
+    settings
+        .bind_property("flash-changes", &flash_check, "active")
+        .bidirectional()
+        .sync_create()
+        .build();
+    grid.attach(&flash_check, 0, row, 2, 1);
+    row += 1;
+
+    let columns_box = Box::new(Orientation::Vertical, 2);
+    for (id, title) in COLUMNS {
+        let check = CheckButton::builder()
+            .label(title)
+            .active(settings.columns().iter().any(|c| c == id))
+            .build();
+
+        check.connect_toggled(glib::clone!(
+            #[weak]
+            settings,
+            move |check| {
+                let mut columns = settings.columns();
+                columns.retain(|c| c != id);
+                if check.is_active() {
+                    columns.push(id.to_string());
+                }
+                settings.set_columns(columns);
+            }
+        ));
+        columns_box.append(&check);
+    }
+    grid.attach(
+        &Label::builder()
+            .label("Columns")
+            .xalign(0.0)
+            .valign(Align::Start)
+            .build(),
+        0,
+        row,
+        1,
+        1,
+    );
+    grid.attach(&columns_box, 1, row, 1, 1);
+
+    Window::builder()
+        .title("Preferences")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(360)
+        .child(&grid)
+        .build()
+        .present();
+}
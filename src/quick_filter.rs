@@ -0,0 +1,106 @@
+use crate::stock_object::StockObject;
+
+/// Reads a number from a stock for a comparison
+type Field = fn(&StockObject) -> f64;
+
+/// Fields that can be compared against a number, with the value they read from a stock.
+/// Changes are in percent, as shown in the list.
+const FIELDS: [(&str, Field); 8] = [
+    ("price", |s| s.price()),
+    ("change1d", |s| s.pct_change_1d() * 100.0),
+    ("change1w", |s| s.pct_change_1w() * 100.0),
+    ("volume", |s| s.volume()),
+    ("marketcap", |s| s.market_cap()),
+    ("quantity", |s| s.quantity()),
+    ("cost", |s| s.cost()),
+    ("value", |s| s.value()),
+];
+
+/// Comparison operators, longest first so `<=` isn't read as `<`
+const OPERATORS: [&str; 6] = ["<=", ">=", "!=", "<", ">", "="];
+
+/// A filter over the watchlist. Terms are separated by whitespace and all have to match:
+///
+/// - `tag:crypto` matches stocks with that tag
+/// - `change1d<-2`, `price>=100` etc. compare a field against a number
+/// - anything else matches the ticker, name or tags as text
+#[derive(Debug, Clone, Default)]
+pub struct QuickFilter {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    Tag(String),
+    Compare {
+        field: Field,
+        operator: &'static str,
+        value: f64,
+    },
+    Text(String),
+}
+
+impl QuickFilter {
+    pub fn parse(query: &str) -> Self {
+        let terms = query
+            .split_whitespace()
+            .map(|term| term.to_lowercase())
+            .map(|term| {
+                if let Some(tag) = term.strip_prefix("tag:") {
+                    return Term::Tag(tag.to_string());
+                }
+                parse_comparison(&term).unwrap_or(Term::Text(term))
+            })
+            .collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, stock: &StockObject) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Tag(tag) => stock.tags().iter().any(|t| t.to_lowercase() == *tag),
+            Term::Compare {
+                field,
+                operator,
+                value,
+            } => {
+                let actual = field(stock);
+                match *operator {
+                    "<=" => actual <= *value,
+                    ">=" => actual >= *value,
+                    "!=" => actual != *value,
+                    "<" => actual < *value,
+                    ">" => actual > *value,
+                    _ => actual == *value,
+                }
+            }
+            Term::Text(text) => {
+                stock.ticker().to_lowercase().contains(text)
+                    || stock.title().to_lowercase().contains(text)
+                    || stock.name().to_lowercase().contains(text)
+                    || stock.tags().iter().any(|t| t.to_lowercase().contains(text))
+            }
+        })
+    }
+}
+
+/// Reads e.g. `change1d<-2`, anything that isn't a known field, operator and number is `None`
+fn parse_comparison(term: &str) -> Option<Term> {
+    let (position, operator) = OPERATORS
+        .iter()
+        .filter_map(|operator| term.find(operator).map(|position| (position, *operator)))
+        .min_by_key(|(position, operator)| (*position, usize::MAX - operator.len()))?;
+
+    let name = &term[..position];
+    let (_, field) = FIELDS.iter().find(|(field, _)| *field == name)?;
+    let value = term[position + operator.len()..].parse().ok()?;
+
+    Some(Term::Compare {
+        field: *field,
+        operator,
+        value,
+    })
+}
@@ -0,0 +1,225 @@
+use crate::persistence::{get_config_dir, write_atomic};
+use gtk::{glib, prelude::*, subclass::prelude::*};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs::read_to_string, path::PathBuf, time::Duration};
+
+/// Column resizes notify on every pixel, so width changes are saved after this delay
+const WIDTH_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// The on-disk format of `settings.json`, missing fields fall back to their defaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct SettingsData {
+    refresh_interval: u32,
+    bullish_threshold: f64,
+    bearish_threshold: f64,
+    window_width: i32,
+    window_height: i32,
+    columns: Vec<String>,
+    column_order: Vec<String>,
+    column_widths: BTreeMap<String, i32>,
+    sort_column: String,
+    sort_descending: bool,
+    group_by: String,
+    flash_changes: bool,
+    theme: String,
+    show_heatmap: bool,
+    heatmap_size: String,
+    heatmap_color: String,
+}
+
+impl Default for SettingsData {
+    fn default() -> Self {
+        Self {
+            refresh_interval: 60,
+            bullish_threshold: 5.0,
+            bearish_threshold: -5.0,
+            window_width: 400,
+            window_height: 400,
+            columns: [
+                "ticker",
+                "name",
+                "sparkline",
+                "price",
+                "change_1w",
+                "change_1d",
+            ]
+            .map(String::from)
+            .to_vec(),
+            column_order: vec![],
+            column_widths: BTreeMap::new(),
+            sort_column: "change_1d".to_string(),
+            sort_descending: true,
+            group_by: String::new(),
+            flash_changes: true,
+            theme: String::new(),
+            show_heatmap: false,
+            heatmap_size: "value".to_string(),
+            heatmap_color: "1d".to_string(),
+        }
+    }
+}
+
+mod imp {
+    use super::*;
+    use glib::Properties;
+    use std::cell::RefCell;
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::AppSettings)]
+    pub struct AppSettings {
+        /// Seconds between price updates
+        #[property(get, set, minimum = 5)]
+        pub refresh_interval: RefCell<u32>,
+        /// Average daily change, in percent, at or above which the Waybar module is bullish
+        #[property(get, set)]
+        pub bullish_threshold: RefCell<f64>,
+        /// Average daily change, in percent, below which the Waybar module is bearish
+        #[property(get, set)]
+        pub bearish_threshold: RefCell<f64>,
+        #[property(get, set)]
+        pub window_width: RefCell<i32>,
+        #[property(get, set)]
+        pub window_height: RefCell<i32>,
+        /// Ids of the visible stock list columns
+        #[property(get, set)]
+        pub columns: RefCell<Vec<String>>,
+        /// Ids of all stock list columns in display order, empty for the default order
+        #[property(get, set)]
+        pub column_order: RefCell<Vec<String>>,
+        /// Id of the column the stock list is sorted by
+        #[property(get, set)]
+        pub sort_column: RefCell<String>,
+        #[property(get, set)]
+        pub sort_descending: RefCell<bool>,
+        /// What the stock list is split into sections by, see `GROUPS`, empty for no sections
+        #[property(get, set)]
+        pub group_by: RefCell<String>,
+        /// Whether rows flash green or red when a refresh moves their price
+        #[property(get, set)]
+        pub flash_changes: RefCell<bool>,
+        /// Id of the theme variant, see `THEMES`, empty for the default look
+        #[property(get, set)]
+        pub theme: RefCell<String>,
+        /// Whether the heatmap is shown in place of the stock list
+        #[property(get, set)]
+        pub show_heatmap: RefCell<bool>,
+        /// What heatmap tiles are sized by, see `heatmap::SIZES`
+        #[property(get, set)]
+        pub heatmap_size: RefCell<String>,
+        /// Which change heatmap tiles are coloured by, see `heatmap::COLORS`
+        #[property(get, set)]
+        pub heatmap_color: RefCell<String>,
+        /// Not a property since nothing reacts to it live, see `set_column_width`
+        pub column_widths: RefCell<BTreeMap<String, i32>>,
+        pub pending_save: RefCell<Option<glib::SourceId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AppSettings {
+        const NAME: &'static str = "AppSettings";
+        type Type = super::AppSettings;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for AppSettings {}
+}
+
+glib::wrapper! {
+    pub struct AppSettings(ObjectSubclass<imp::AppSettings>);
+}
+
+impl AppSettings {
+    /// Loads `settings.json`, every later property change is saved back to it
+    pub fn load() -> Self {
+        let data = read_to_string(settings_path())
+            .ok()
+            .and_then(|json| serde_json::from_str::<SettingsData>(&json).ok())
+            .unwrap_or_default();
+
+        let settings: Self = glib::Object::builder()
+            .property("refresh-interval", data.refresh_interval.max(5))
+            .property("bullish-threshold", data.bullish_threshold)
+            .property("bearish-threshold", data.bearish_threshold)
+            .property("window-width", data.window_width)
+            .property("window-height", data.window_height)
+            .property("columns", data.columns)
+            .property("column-order", data.column_order)
+            .property("sort-column", data.sort_column)
+            .property("sort-descending", data.sort_descending)
+            .property("group-by", data.group_by)
+            .property("flash-changes", data.flash_changes)
+            .property("theme", data.theme)
+            .property("show-heatmap", data.show_heatmap)
+            .property("heatmap-size", data.heatmap_size)
+            .property("heatmap-color", data.heatmap_color)
+            .build();
+        settings.imp().column_widths.replace(data.column_widths);
+
+        settings.connect_notify_local(None, |settings, _| settings.save());
+        settings
+    }
+
+    pub fn column_width(&self, id: &str) -> Option<i32> {
+        self.imp().column_widths.borrow().get(id).copied()
+    }
+
+    pub fn set_column_width(&self, id: &str, width: i32) {
+        let previous = self
+            .imp()
+            .column_widths
+            .borrow_mut()
+            .insert(id.to_string(), width);
+        if previous == Some(width) {
+            return;
+        }
+
+        if let Some(source) = self.imp().pending_save.take() {
+            source.remove();
+        }
+        let source = glib::timeout_add_local_once(
+            WIDTH_SAVE_DELAY,
+            glib::clone!(
+                #[weak(rename_to = settings)]
+                self,
+                move || {
+                    settings.imp().pending_save.take();
+                    settings.save();
+                }
+            ),
+        );
+        self.imp().pending_save.replace(Some(source));
+    }
+
+    fn save(&self) {
+        let data = SettingsData {
+            refresh_interval: self.refresh_interval(),
+            bullish_threshold: self.bullish_threshold(),
+            bearish_threshold: self.bearish_threshold(),
+            window_width: self.window_width(),
+            window_height: self.window_height(),
+            columns: self.columns(),
+            column_order: self.column_order(),
+            column_widths: self.imp().column_widths.borrow().clone(),
+            sort_column: self.sort_column(),
+            sort_descending: self.sort_descending(),
+            group_by: self.group_by(),
+            flash_changes: self.flash_changes(),
+            theme: self.theme(),
+            show_heatmap: self.show_heatmap(),
+            heatmap_size: self.heatmap_size(),
+            heatmap_color: self.heatmap_color(),
+        };
+
+        let result = serde_json::to_string_pretty(&data)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| write_atomic(&settings_path(), json.as_bytes()));
+        if let Err(e) = result {
+            eprintln!("Failed to save settings: {}", e);
+        }
+    }
+}
+
+fn settings_path() -> PathBuf {
+    get_config_dir().join("settings.json")
+}
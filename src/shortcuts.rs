@@ -0,0 +1,84 @@
+use crate::stock_manager::StockManager;
+use gtk::{
+    Application, ApplicationWindow, Builder, DropDown, SearchBar, SearchEntry, ShortcutsWindow,
+    gio::SimpleAction,
+    glib::{self, VariantTy},
+    prelude::*,
+};
+use std::rc::Rc;
+
+/// Registers the keyboard driven actions with their accelerators, and `app.shortcuts`
+/// which lists every shortcut. Removing with Delete and opening the chart with Enter are
+/// handled by the stock list itself.
+pub fn add_actions(
+    application: &Application,
+    window: &ApplicationWindow,
+    manager: Rc<StockManager>,
+    search_entry: &SearchEntry,
+    quick_filter: &SearchBar,
+    tag_filter: &DropDown,
+) {
+    let refresh_action = SimpleAction::new("refresh", None);
+    refresh_action.connect_activate(move |_, _| manager.update_stocks());
+    application.add_action(&refresh_action);
+    application.set_accels_for_action("app.refresh", &["<Control>r"]);
+
+    let search_action = SimpleAction::new("search", None);
+    search_action.connect_activate(glib::clone!(
+        #[weak]
+        search_entry,
+        move |_, _| {
+            search_entry.grab_focus();
+        }
+    ));
+    application.add_action(&search_action);
+    application.set_accels_for_action("app.search", &["<Control>l"]);
+
+    let filter_action = SimpleAction::new("filter", None);
+    filter_action.connect_activate(glib::clone!(
+        #[weak]
+        quick_filter,
+        move |_, _| quick_filter.set_search_mode(true)
+    ));
+    application.add_action(&filter_action);
+    application.set_accels_for_action("app.filter", &["<Control>f"]);
+
+    // Switches between the tag views of the watchlist, the first entry shows all tags
+    let tag_action = SimpleAction::new("select-tag", Some(VariantTy::INT32));
+    tag_action.connect_activate(glib::clone!(
+        #[weak]
+        tag_filter,
+        move |_, target| {
+            let Some(position) = target.and_then(|t| t.get::<i32>()) else {
+                return;
+            };
+            let in_range = tag_filter
+                .model()
+                .is_some_and(|model| (position as u32) < model.n_items());
+            if position >= 0 && in_range {
+                tag_filter.set_selected(position as u32);
+            }
+        }
+    ));
+    application.add_action(&tag_action);
+    for position in 0..9 {
+        application.set_accels_for_action(
+            &format!("app.select-tag({})", position),
+            &[&format!("<Control>{}", position + 1)],
+        );
+    }
+
+    let shortcuts_action = SimpleAction::new("shortcuts", None);
+    shortcuts_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        move |_, _| {
+            let builder = Builder::from_string(include_str!("../resources/shortcuts.ui"));
+            let shortcuts_window: ShortcutsWindow = builder.object("shortcuts_window").unwrap();
+            shortcuts_window.set_transient_for(Some(&window));
+            shortcuts_window.present();
+        }
+    ));
+    application.add_action(&shortcuts_action);
+    application.set_accels_for_action("app.shortcuts", &["<Control>question"]);
+}
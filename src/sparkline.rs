@@ -0,0 +1,139 @@
+use crate::stock_object::{PriceSeries, StockObject};
+use gtk::{
+    DrawingArea,
+    cairo::Context,
+    glib::{self, SignalHandlerId},
+    prelude::*,
+};
+
+const WIDTH: i32 = 80;
+const HEIGHT: i32 = 20;
+
+/// Key of the intraday notify handler kept on each area, so unbind can disconnect it
+const INTRADAY_HANDLER: &str = "intraday-handler";
+
+pub fn create_sparkline() -> DrawingArea {
+    DrawingArea::builder()
+        .content_width(WIDTH)
+        .content_height(HEIGHT)
+        .build()
+}
+
+/// Draws the intraday prices of `stock` into `area` and redraws whenever they change,
+/// until `unbind_sparkline`. The line uses the `success`/`error` CSS colours, so it
+/// follows the theme.
+pub fn bind_sparkline(area: &DrawingArea, stock: &StockObject) {
+    area.set_draw_func(glib::clone!(
+        #[weak]
+        stock,
+        move |area, cr, width, height| {
+            draw_series(
+                cr,
+                &stock.intraday(),
+                width as f64,
+                height as f64,
+                area.color(),
+            );
+        }
+    ));
+    update_direction(area, &stock.intraday());
+    area.queue_draw();
+
+    let handler = stock.connect_notify_local(
+        Some("intraday"),
+        glib::clone!(
+            #[weak]
+            area,
+            move |stock, _| {
+                update_direction(&area, &stock.intraday());
+                area.queue_draw();
+            }
+        ),
+    );
+    // SAFETY: Only ever stored and taken as this type, see `unbind_sparkline`
+    unsafe { area.set_data(INTRADAY_HANDLER, (stock.clone(), handler)) };
+}
+
+/// Stops `area` following the stock it was bound to, so a recycled row only shows its own
+pub fn unbind_sparkline(area: &DrawingArea) {
+    // SAFETY: Set in `bind_sparkline`
+    if let Some((stock, handler)) =
+        unsafe { area.steal_data::<(StockObject, SignalHandlerId)>(INTRADAY_HANDLER) }
+    {
+        stock.disconnect(handler);
+    }
+}
+
+fn update_direction(area: &DrawingArea, series: &PriceSeries) {
+    let (first, last) = match (series.closes.first(), series.closes.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => {
+            area.remove_css_class("success");
+            area.remove_css_class("error");
+            return;
+        }
+    };
+    let baseline = if series.prev_close > 0.0 {
+        series.prev_close
+    } else {
+        first
+    };
+
+    if last >= baseline {
+        area.add_css_class("success");
+        area.remove_css_class("error");
+    } else {
+        area.add_css_class("error");
+        area.remove_css_class("success");
+    }
+}
+
+fn draw_series(cr: &Context, series: &PriceSeries, width: f64, height: f64, color: gtk::gdk::RGBA) {
+    if series.closes.len() < 2 {
+        return;
+    }
+
+    // The previous close is part of the range, so the baseline is always visible
+    let mut min = series.closes.iter().copied().fold(f64::INFINITY, f64::min);
+    let mut max = series
+        .closes
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    if series.prev_close > 0.0 {
+        min = min.min(series.prev_close);
+        max = max.max(series.prev_close);
+    }
+    let span = if max > min { max - min } else { 1.0 };
+
+    let padding = 2.0;
+    let y = |price: f64| padding + (max - price) / span * (height - 2.0 * padding);
+    let step = width / (series.closes.len() - 1) as f64;
+
+    cr.set_line_width(1.0);
+    if series.prev_close > 0.0 {
+        cr.set_source_rgba(
+            color.red() as f64,
+            color.green() as f64,
+            color.blue() as f64,
+            0.4,
+        );
+        cr.set_dash(&[2.0, 2.0], 0.0);
+        cr.move_to(0.0, y(series.prev_close));
+        cr.line_to(width, y(series.prev_close));
+        let _ = cr.stroke();
+        cr.set_dash(&[], 0.0);
+    }
+
+    cr.set_source_rgba(
+        color.red() as f64,
+        color.green() as f64,
+        color.blue() as f64,
+        color.alpha() as f64,
+    );
+    cr.set_line_width(1.5);
+    for (i, close) in series.closes.iter().enumerate() {
+        cr.line_to(i as f64 * step, y(*close));
+    }
+    let _ = cr.stroke();
+}
@@ -1,8 +1,24 @@
+use crate::stock_object::PriceSeries;
 use anyhow::{Result, anyhow};
-use yahoo_finance_api::YahooConnector;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+use yahoo_finance_api::{YSearchResult, YahooConnector, YahooError};
+
+/// How long a fetched history is reused, e.g. when switching chart ranges back and forth
+const HISTORY_TTL: Duration = Duration::from_secs(300);
+
+/// Ticker, interval and range of a cached history
+type HistoryKey = (String, String, String);
 
 pub struct StockApi {
     provider: YahooConnector,
+    /// Quote summaries need a crumb that the connector caches, which takes `&mut self`
+    summary_provider: Mutex<YahooConnector>,
+    history_cache: RefCell<HashMap<HistoryKey, (Instant, History)>>,
 }
 
 impl Default for StockApi {
@@ -15,12 +31,60 @@ pub struct RangeResponse {
     pub prev_close: f64,
     pub last_close: f64,
     pub pct_change: f64,
+    pub volume: f64,
+    /// Yahoo's quote type, e.g. "EQUITY", "ETF" or "CRYPTOCURRENCY"
+    pub instrument_type: String,
+    pub exchange: String,
+}
+
+/// One step of a price history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+/// Outcome of `StockApi::validate_symbol`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolStatus {
+    Valid,
+    /// No prices, but another listing was found that likely replaced it
+    Renamed {
+        symbol: String,
+        name: String,
+    },
+    /// No prices and nothing to replace it, e.g. delisted
+    Missing,
+    /// Yahoo couldn't be asked, e.g. while offline or rate limited
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct History {
+    pub candles: Vec<Candle>,
+    pub currency: String,
+    pub exchange: String,
+    pub prev_close: f64,
+    pub day_low: f64,
+    pub day_high: f64,
+    pub year_low: f64,
+    pub year_high: f64,
+    pub volume: f64,
 }
 
 impl StockApi {
     pub fn new() -> Self {
         let provider = YahooConnector::new().unwrap();
-        Self { provider }
+        let summary_provider = Mutex::new(YahooConnector::new().unwrap());
+        Self {
+            provider,
+            summary_provider,
+            history_cache: RefCell::new(HashMap::new()),
+        }
     }
 
     /// Returns the first and last close for a weekly range
@@ -33,15 +97,152 @@ impl StockApi {
         self.range(ticker, "1d", "1d").await
     }
 
-    /// Search for a ticker
-    pub async fn search_ticker(&self, query: &str) -> Vec<(String, String)> {
-        match self.provider.search_ticker(query).await {
-            Ok(resp) => resp
-                .quotes
-                .iter()
-                .map(|i| (i.symbol.clone(), i.short_name.clone()))
-                .collect(),
-            Err(_) => vec![],
+    /// Returns today's prices in 5 minute steps
+    pub async fn intraday(&self, ticker: &str) -> Result<PriceSeries> {
+        let response = self
+            .provider
+            .get_quote_range(ticker, "5m", "1d")
+            .await
+            .map_err(|e| anyhow!("Error fetching {}: {}", ticker, e))?;
+
+        let prev_close = response
+            .metadata()?
+            .chart_previous_close
+            .unwrap_or_default();
+        let quotes = response.quotes()?;
+
+        Ok(PriceSeries {
+            timestamps: quotes.iter().map(|q| q.timestamp).collect(),
+            closes: quotes.iter().map(|q| q.close).collect(),
+            prev_close,
+        })
+    }
+
+    /// Returns the price history over `range` in steps of `interval`, using Yahoo's names
+    /// for both, e.g. "5m" and "1d". Histories are cached for a few minutes.
+    pub async fn history(&self, ticker: &str, interval: &str, range: &str) -> Result<History> {
+        let key = (ticker.to_string(), interval.to_string(), range.to_string());
+        if let Some((fetched, history)) = self.history_cache.borrow().get(&key)
+            && fetched.elapsed() < HISTORY_TTL
+        {
+            return Ok(history.clone());
+        }
+
+        let history = self.fetch_history(ticker, interval, range).await?;
+        let mut cache = self.history_cache.borrow_mut();
+        cache.retain(|_, (fetched, _)| fetched.elapsed() < HISTORY_TTL);
+        cache.insert(key, (Instant::now(), history.clone()));
+        Ok(history)
+    }
+
+    async fn fetch_history(&self, ticker: &str, interval: &str, range: &str) -> Result<History> {
+        let response = self
+            .provider
+            .get_quote_range(ticker, interval, range)
+            .await
+            .map_err(|e| anyhow!("Error fetching {}: {}", ticker, e))?;
+
+        let meta = response.metadata()?;
+        let candles = response
+            .quotes()?
+            .iter()
+            .map(|q| Candle {
+                timestamp: q.timestamp,
+                open: q.open,
+                high: q.high,
+                low: q.low,
+                close: q.close,
+                volume: q.volume as f64,
+            })
+            .collect();
+
+        Ok(History {
+            candles,
+            currency: meta.currency.unwrap_or_default(),
+            exchange: meta.full_exchange_name,
+            prev_close: meta
+                .previous_close
+                .or(meta.chart_previous_close)
+                .unwrap_or_default(),
+            day_low: meta.regular_market_day_low.unwrap_or_default(),
+            day_high: meta.regular_market_day_high.unwrap_or_default(),
+            year_low: meta.fifty_two_week_low.unwrap_or_default(),
+            year_high: meta.fifty_two_week_high.unwrap_or_default(),
+            volume: meta.regular_market_volume.unwrap_or_default(),
+        })
+    }
+
+    /// Returns the market capitalization of a ticker
+    pub async fn market_cap(&self, ticker: &str) -> Result<f64> {
+        let summary = self
+            .summary_provider
+            .lock()
+            .await
+            .get_ticker_info(ticker)
+            .await
+            .map_err(|e| anyhow!("Error fetching summary for {}: {}", ticker, e))?;
+
+        summary
+            .quote_summary
+            .and_then(|s| s.result)
+            .and_then(|results| results.into_iter().next())
+            .and_then(|data| data.summary_detail)
+            .and_then(|detail| detail.market_cap)
+            .map(|cap| cap as f64)
+            .ok_or_else(|| anyhow!("No market cap for {}", ticker))
+    }
+
+    /// Search for a ticker, errors are for searches that didn't get an answer
+    pub async fn search_ticker(&self, query: &str) -> Result<Vec<(String, String)>> {
+        self.provider
+            .search_ticker(query)
+            .await
+            .map(search_results)
+            .map_err(|e| anyhow!("Error searching for {}: {}", query, e))
+    }
+
+    /// Checks that `ticker` still returns prices. If it doesn't, the first other listing
+    /// found by searching for the ticker, then for `name`, is taken as its replacement.
+    /// Only symbols Yahoo answered for without prices are reported, a failed request makes
+    /// the status `Unknown`.
+    pub async fn validate_symbol(&self, ticker: &str, name: &str) -> SymbolStatus {
+        match self.has_prices(ticker).await {
+            Ok(true) => return SymbolStatus::Valid,
+            Ok(false) => {}
+            Err(_) => return SymbolStatus::Unknown,
+        }
+
+        for query in [ticker, name] {
+            if query.is_empty() {
+                continue;
+            }
+            let Ok(results) = self.search_ticker(query).await else {
+                return SymbolStatus::Unknown;
+            };
+            let Some((symbol, name)) = results.into_iter().find(|(symbol, _)| symbol != ticker)
+            else {
+                continue;
+            };
+            match self.has_prices(&symbol).await {
+                Ok(true) => return SymbolStatus::Renamed { symbol, name },
+                Ok(false) => {}
+                Err(_) => return SymbolStatus::Unknown,
+            }
+        }
+        SymbolStatus::Missing
+    }
+
+    /// Whether Yahoo has today's prices for `ticker`. Errors are for requests that didn't
+    /// get an answer, as opposed to an answer without prices.
+    async fn has_prices(&self, ticker: &str) -> Result<bool> {
+        match self.provider.get_quote_range(ticker, "1d", "1d").await {
+            Ok(response) => Ok(response
+                .metadata()
+                .is_ok_and(|meta| meta.chart_previous_close.is_some())
+                && response.last_quote().is_ok()),
+            // Unknown symbols come back as an API error, delisted ones without quotes
+            Err(YahooError::ApiError(_) | YahooError::NoResult | YahooError::NoQuotes) => Ok(false),
+            Err(e) => Err(anyhow!("Error fetching {}: {}", ticker, e)),
         }
     }
 
@@ -59,17 +260,45 @@ impl StockApi {
             Ok(response) => {
                 let meta = response.metadata()?;
 
-                let prev_close = meta.chart_previous_close.unwrap();
+                // Delisted symbols can still answer, just without prices
+                let prev_close = meta
+                    .chart_previous_close
+                    .ok_or_else(|| anyhow!("No prices for {}", ticker))?;
                 let last_close = response.last_quote()?.close;
                 let pct_change = (last_close - prev_close) / prev_close;
+                let volume = meta.regular_market_volume.unwrap_or_default();
 
                 Ok(RangeResponse {
                     prev_close,
                     last_close,
                     pct_change,
+                    volume,
+                    instrument_type: meta.instrument_type,
+                    exchange: meta.full_exchange_name,
                 })
             }
             Err(e) => Err(anyhow!("Error fetching {}: {}", ticker, e)),
         }
     }
 }
+
+/// Searches for a ticker on a connector of its own. Unlike the `StockApi` methods this can
+/// run on another thread, so the UI doesn't wait for the results.
+pub async fn search_tickers(query: &str) -> Vec<(String, String)> {
+    match YahooConnector::new() {
+        Ok(provider) => provider
+            .search_ticker(query)
+            .await
+            .map(search_results)
+            .unwrap_or_default(),
+        Err(_) => vec![],
+    }
+}
+
+fn search_results(response: YSearchResult) -> Vec<(String, String)> {
+    response
+        .quotes
+        .iter()
+        .map(|i| (i.symbol.clone(), i.short_name.clone()))
+        .collect()
+}
@@ -1,75 +1,390 @@
 use crate::{
-    dbus::StockfinBusState, persistence::save_tickers, stock_api::StockApi,
+    compare_window::show_compare_window,
+    dbus::StockfinBusState,
+    detail_window::show_detail_window,
+    edit_dialog::show_edit_dialog,
+    persistence::{
+        ImportEntry, TickerEntry, get_config_dir, get_config_path, merge_entries, read_tickers,
+        save_tickers,
+    },
+    quick_filter::QuickFilter,
+    settings::AppSettings,
+    sparkline::{bind_sparkline, create_sparkline, unbind_sparkline},
+    stock_api::{StockApi, search_tickers},
     stock_object::StockObject,
+    summary::PortfolioSummary,
+    toast::Toast,
 };
 use gtk::{
-    Align, Box, ColumnView, ColumnViewColumn, CustomSorter, GestureClick, INVALID_LIST_POSITION,
-    Label, ListBox, ListBoxRow, Orientation, Popover, PopoverMenu, PopoverMenuFlags, PositionType,
-    ScrolledWindow, SearchEntry, SignalListItemFactory, SingleSelection, SortListModel,
-    SorterChange,
-    gdk::Rectangle,
-    gio::{ListStore, Menu, SimpleAction, SimpleActionGroup, prelude::*},
+    Align, Box, Button, ColumnView, ColumnViewColumn, ColumnViewSorter, CustomFilter, CustomSorter,
+    DragSource, DrawingArea, DropDown, DropTarget, EventControllerFocus, EventControllerKey,
+    EveryFilter, FilterChange, FilterListModel, GestureClick, Label, ListBox, ListBoxRow,
+    ListHeader, ListItem, ListScrollFlags, MultiSelection, NamedAction, Orientation, PickFlags,
+    Popover, PopoverMenu, PopoverMenuFlags, PositionType, PropagationPhase, ScrolledWindow,
+    SearchBar, SearchEntry, SelectionMode, Shortcut, ShortcutController, ShortcutTrigger,
+    SignalListItemFactory, SortListModel, SortType, SorterChange, Spinner, Stack, StringList,
+    StringObject, Widget, Window,
+    gdk::{self, ContentProvider, DragAction, Rectangle},
+    gio::{
+        self, FileMonitor, FileMonitorEvent, FileMonitorFlags, ListStore, Menu, MenuItem,
+        SimpleAction, SimpleActionGroup, prelude::*,
+    },
     glib::{
-        self,
+        self, Binding, JoinHandle, SignalHandlerId,
         object::{Cast, CastNone, ObjectExt},
     },
     pango::EllipsizeMode,
     prelude::*,
 };
 use std::{
+    cell::{Cell, RefCell},
     cmp::Ordering,
+    collections::{BTreeSet, HashSet},
     rc::Rc,
     sync::{Arc, atomic},
+    time::Duration,
 };
 
+/// Ids and titles of the stock list columns, in display order
+pub const COLUMNS: [(&str, &str); 9] = [
+    ("ticker", "Ticker"),
+    ("name", "Stock"),
+    ("sparkline", "Today"),
+    ("price", "Price"),
+    ("change_1w", "Change (1w)"),
+    ("change_1d", "Change (1d)"),
+    ("volume", "Volume"),
+    ("market_cap", "Market cap"),
+    ("value", "Value"),
+];
+
+/// Ids and titles of what the stock list can be split into sections by
+pub const GROUPS: [(&str, &str); 4] = [
+    ("", "None"),
+    ("tag", "Tag"),
+    ("type", "Asset Type"),
+    ("exchange", "Exchange"),
+];
+
+/// How long a row stays highlighted after its price moves, the stylesheet fades it out
+const FLASH_DURATION: Duration = Duration::from_millis(800);
+
+/// Key of the price notify handler kept on each price label, so unbind can disconnect it
+const FLASH_HANDLER: &str = "flash-handler";
+
+/// Key of the name notify handlers kept on each name label, so unbind can disconnect them
+const TITLE_HANDLERS: &str = "title-handlers";
+
+/// Keys of the property bindings and notify handlers kept on the labels of the numeric
+/// columns, see `unbind_cell`
+const CELL_BINDINGS: &str = "cell-bindings";
+const CELL_HANDLERS: &str = "cell-handlers";
+
+/// How long typing has to pause before the ticker search runs
+const SEARCH_DELAY_MS: u32 = 300;
+
+type UpdatedCallback = std::boxed::Box<dyn Fn()>;
+
 pub struct StockManager {
     api: Rc<StockApi>,
     stocks: ListStore,
     sorted_stocks: SortListModel,
+    /// Every tag in use, for the tag filter
+    tags: StringList,
+    selected_tag: Rc<RefCell<Option<String>>>,
+    tag_filter: CustomFilter,
+    quick_filter: Rc<RefCell<QuickFilter>>,
+    quick_filter_model: CustomFilter,
     bus_state: Arc<StockfinBusState>,
+    settings: AppSettings,
+    toast: Toast,
+    /// Whether `update_stocks` is running, and whether it was triggered again meanwhile
+    refreshing: Rc<Cell<bool>>,
+    refresh_pending: Rc<Cell<bool>>,
+    refresh_spinner: Spinner,
+    updated_label: Label,
+    /// Run after every refresh, see `connect_updated`
+    updated_callbacks: Rc<RefCell<Vec<UpdatedCallback>>>,
+    /// The stock list, once created, to bring rows into view
+    column_view: Rc<glib::WeakRef<ColumnView>>,
+    config_monitor: RefCell<Option<FileMonitor>>,
 }
 
 impl StockManager {
-    pub fn new(tickers: &[(String, String)], bus_state: Arc<StockfinBusState>) -> Self {
-        let sorter = CustomSorter::new(move |a, b| {
-            let stock1 = a.downcast_ref::<StockObject>().unwrap();
-            let stock2 = b.downcast_ref::<StockObject>().unwrap();
-
-            stock2
-                .pct_change_1d()
-                .partial_cmp(&stock1.pct_change_1d())
-                .unwrap_or(Ordering::Equal)
-                .into()
-        });
+    pub fn new(
+        tickers: &[TickerEntry],
+        bus_state: Arc<StockfinBusState>,
+        settings: AppSettings,
+    ) -> Self {
+        let selected_tag: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+        let tag_filter = CustomFilter::new(glib::clone!(
+            #[strong]
+            selected_tag,
+            move |obj| {
+                let stock = obj.downcast_ref::<StockObject>().unwrap();
+                match selected_tag.borrow().as_ref() {
+                    Some(tag) => stock.tags().contains(tag),
+                    None => true,
+                }
+            }
+        ));
+
+        let quick_filter = Rc::new(RefCell::new(QuickFilter::default()));
+        let quick_filter_model = CustomFilter::new(glib::clone!(
+            #[strong]
+            quick_filter,
+            move |obj| {
+                let stock = obj.downcast_ref::<StockObject>().unwrap();
+                quick_filter.borrow().matches(stock)
+            }
+        ));
+
+        let filter = EveryFilter::new();
+        filter.append(tag_filter.clone());
+        filter.append(quick_filter_model.clone());
 
         let api = Rc::new(StockApi::new());
         let stocks = ListStore::new::<StockObject>();
-        let sorted_stocks = SortListModel::new(Some(stocks.clone()), Some(sorter));
+        let filtered_stocks = FilterListModel::new(Some(stocks.clone()), Some(filter));
+        // The sorter is taken from the column view once it exists
+        let sorted_stocks = SortListModel::new(Some(filtered_stocks), None::<CustomSorter>);
 
-        for (ticker, name) in tickers {
-            stocks.append(&StockObject::new(ticker, name));
+        for entry in tickers {
+            stocks.append(&StockObject::from_entry(entry));
         }
 
+        let tags = StringList::new(&[]);
+        refresh_tags(&stocks, &tags, &tag_filter);
+
         let manager = Self {
             api,
             stocks,
             sorted_stocks,
+            tags,
+            selected_tag,
+            tag_filter,
+            quick_filter,
+            quick_filter_model,
             bus_state,
+            settings,
+            toast: Toast::new(),
+            refreshing: Rc::new(Cell::new(false)),
+            refresh_pending: Rc::new(Cell::new(false)),
+            refresh_spinner: Spinner::new(),
+            updated_label: Label::builder()
+                .css_classes(["dim-label", "numeric"])
+                .build(),
+            updated_callbacks: Rc::default(),
+            column_view: Rc::new(glib::WeakRef::new()),
+            config_monitor: RefCell::new(None),
         };
 
         manager.update_stocks();
         manager
     }
 
+    pub fn api(&self) -> Rc<StockApi> {
+        self.api.clone()
+    }
+
+    /// The watchlist as shown, filtered and sorted
+    pub fn sorted_stocks(&self) -> SortListModel {
+        self.sorted_stocks.clone()
+    }
+
+    pub fn toast(&self) -> &Toast {
+        &self.toast
+    }
+
+    /// Returns the watchlist in store order
+    pub fn entries(&self) -> Vec<TickerEntry> {
+        store_entries(&self.stocks)
+    }
+
+    /// Adds new tickers and updates holdings and tags of existing ones, then saves. Values
+    /// an import doesn't have are kept, see `ImportEntry`.
+    pub fn import_entries(&self, incoming: &[ImportEntry]) {
+        let mut merged = self.entries();
+        merge_entries(&mut merged, incoming);
+
+        for entry in &merged {
+            match self.find_stock(&entry.ticker) {
+                Some(stock) => stock.apply_entry(entry),
+                None => self.stocks.append(&StockObject::from_entry(entry)),
+            }
+        }
+
+        refresh_tags(&self.stocks, &self.tags, &self.tag_filter);
+        save_store(&self.stocks, &self.toast);
+        self.update_stocks();
+    }
+
+    /// Replaces the whole watchlist and saves it, e.g. when restoring a backup
+    pub fn replace_entries(&self, entries: &[TickerEntry]) {
+        self.reconcile_entries(entries);
+        save_store(&self.stocks, &self.toast);
+    }
+
+    /// Brings the store in line with `entries`, reusing the existing objects (and their
+    /// quotes) for tickers that are still present
+    pub fn reconcile_entries(&self, entries: &[TickerEntry]) {
+        let current: Vec<StockObject> = (0..self.stocks.n_items())
+            .filter_map(|i| self.stocks.item(i).and_downcast::<StockObject>())
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut added = false;
+        let stocks: Vec<StockObject> = entries
+            .iter()
+            .filter(|entry| seen.insert(entry.ticker.clone()))
+            .map(
+                |entry| match current.iter().find(|s| s.ticker() == entry.ticker) {
+                    Some(stock) => {
+                        if stock.to_entry() != *entry {
+                            stock.apply_entry(entry);
+                        }
+                        stock.clone()
+                    }
+                    None => {
+                        added = true;
+                        StockObject::from_entry(entry)
+                    }
+                },
+            )
+            .collect();
+
+        if stocks != current {
+            self.stocks.splice(0, self.stocks.n_items(), &stocks);
+        }
+        refresh_tags(&self.stocks, &self.tags, &self.tag_filter);
+        if added {
+            self.update_stocks();
+        }
+    }
+
+    /// Reloads the watchlist whenever `tickers.json` changes on disk, so hand edits made
+    /// while the app is running aren't overwritten by the next save
+    pub fn watch_config(self: &Rc<Self>) {
+        let monitor = match gio::File::for_path(get_config_dir())
+            .monitor_directory(FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)
+        {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                eprintln!("Could not watch config directory: {}", e);
+                return;
+            }
+        };
+
+        let config_path = get_config_path();
+        let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+        let manager = Rc::downgrade(self);
+
+        monitor.connect_changed(move |_, file, other_file, event| {
+            let is_config = |f: &gio::File| f.path().as_deref() == Some(config_path.as_path());
+            if event == FileMonitorEvent::Deleted
+                || !(is_config(file) || other_file.is_some_and(is_config))
+            {
+                return;
+            }
+
+            // Editors and our own atomic saves touch the file several times, wait for it
+            // to settle before reading
+            if let Some(source) = pending.borrow_mut().take() {
+                source.remove();
+            }
+            let source = glib::timeout_add_local_once(
+                Duration::from_millis(300),
+                glib::clone!(
+                    #[strong]
+                    pending,
+                    #[strong]
+                    manager,
+                    move || {
+                        pending.borrow_mut().take();
+                        let Some(manager) = manager.upgrade() else {
+                            return;
+                        };
+                        if !get_config_path().exists() {
+                            return;
+                        }
+                        match read_tickers() {
+                            Ok(entries) => manager.reconcile_entries(&entries),
+                            Err(e) => {
+                                eprintln!("Ignoring external change: {}", e);
+                                manager
+                                    .toast
+                                    .show("tickers.json has errors, ignoring the change");
+                            }
+                        }
+                    }
+                ),
+            );
+            pending.replace(Some(source));
+        });
+
+        self.config_monitor.replace(Some(monitor));
+    }
+
+    /// Swaps the symbol of `old` for `new`, keeping its tags, notes and holdings, then saves
+    pub fn replace_ticker(&self, old: &str, new: &str, name: &str) {
+        let mut entries = self.entries();
+        if entries.iter().any(|e| e.ticker == new) {
+            entries.retain(|e| e.ticker != old);
+        } else if let Some(entry) = entries.iter_mut().find(|e| e.ticker == old) {
+            entry.ticker = new.to_string();
+            entry.name = name.to_string();
+        }
+        self.replace_entries(&entries);
+    }
+
+    pub fn remove_ticker(&self, ticker: &str) {
+        let mut entries = self.entries();
+        entries.retain(|e| e.ticker != ticker);
+        self.replace_entries(&entries);
+    }
+
+    /// Selects the row of `ticker` and scrolls to it, false if it isn't shown
+    pub fn focus_stock(&self, ticker: &str) -> bool {
+        self.column_view
+            .upgrade()
+            .is_some_and(|column_view| focus_row(&column_view, ticker))
+    }
+
+    pub fn find_stock(&self, ticker: &str) -> Option<StockObject> {
+        (0..self.stocks.n_items())
+            .filter_map(|i| self.stocks.item(i).and_downcast::<StockObject>())
+            .find(|stock| stock.ticker() == ticker)
+    }
+
+    /// Runs `callback` after every refresh, once all prices are in
+    pub fn connect_updated(&self, callback: impl Fn() + 'static) {
+        self.updated_callbacks
+            .borrow_mut()
+            .push(std::boxed::Box::new(callback));
+    }
+
+    /// Fetches fresh prices for every stock. A refresh requested while one is running is
+    /// coalesced into a single follow-up run, so refreshes never overlap on the same objects.
     pub fn update_stocks(&self) {
+        if self.refreshing.get() {
+            self.refresh_pending.set(true);
+            return;
+        }
+        self.refreshing.set(true);
+        self.refresh_spinner.set_spinning(true);
+
         let api = self.api.clone();
         let bus_state = self.bus_state.clone();
+        let stocks = self.stocks.clone();
         let sorted_stocks = self.sorted_stocks.clone();
-
-        let stocks_vec: Vec<StockObject> = (0..self.stocks.n_items())
-            .filter_map(|i| self.stocks.item(i))
-            .filter_map(|item| item.downcast::<StockObject>().ok())
-            .collect();
+        let settings = self.settings.clone();
+        let refreshing = self.refreshing.clone();
+        let refresh_pending = self.refresh_pending.clone();
+        let refresh_spinner = self.refresh_spinner.clone();
+        let updated_label = self.updated_label.clone();
+        let updated_callbacks = self.updated_callbacks.clone();
+        let quick_filter = self.quick_filter.clone();
+        let quick_filter_model = self.quick_filter_model.clone();
 
         glib::MainContext::default().spawn_local(async move {
             let rt = tokio::runtime::Builder::new_current_thread()
@@ -77,9 +392,15 @@ impl StockManager {
                 .build()
                 .unwrap();
 
-            let updated_stocks: Vec<_> = stocks_vec
-                .iter()
-                .map(|stock| {
+            loop {
+                let fetch_market_cap = settings.columns().iter().any(|c| c == "market_cap")
+                    || (settings.show_heatmap() && settings.heatmap_size() == "market_cap");
+                let fetch_intraday = settings.columns().iter().any(|c| c == "sparkline");
+                let updated_stocks: Vec<StockObject> = (0..stocks.n_items())
+                    .filter_map(|i| stocks.item(i).and_downcast::<StockObject>())
+                    .collect();
+
+                for stock in &updated_stocks {
                     let api = api.clone();
                     let ticker = stock.ticker();
 
@@ -92,31 +413,161 @@ impl StockManager {
                         if let Ok(res) = api.daily_range(&ticker).await {
                             stock.set_pct_change_1d(res.pct_change);
                             stock.set_price(res.last_close);
+                            stock.set_volume(res.volume);
+                            stock.set_quote_type(res.instrument_type);
+                            stock.set_exchange(res.exchange);
                         }
-                        stock
-                    })
-                })
-                .collect();
+                        // These cost an extra request per ticker, so only when they're shown
+                        if fetch_market_cap && let Ok(market_cap) = api.market_cap(&ticker).await {
+                            stock.set_market_cap(market_cap);
+                        }
+                        if fetch_intraday && let Ok(series) = api.intraday(&ticker).await {
+                            stock.set_intraday(series);
+                        }
+                    });
 
-            if let Some(sorter) = sorted_stocks.sorter() {
-                sorter.changed(SorterChange::Different);
-            }
+                    // Let the main loop draw and handle input between tickers
+                    glib::timeout_future(Duration::ZERO).await;
+                }
+
+                if let Some(sorter) = sorted_stocks.sorter() {
+                    sorter.changed(SorterChange::Different);
+                }
+                if let Some(section_sorter) = sorted_stocks.section_sorter() {
+                    section_sorter.changed(SorterChange::Different);
+                }
+                // Comparisons like `change1d<-2` depend on the new prices
+                if !quick_filter.borrow().is_empty() {
+                    quick_filter_model.changed(FilterChange::Different);
+                }
+
+                if !updated_stocks.is_empty() {
+                    let total: f64 = updated_stocks.iter().map(|s| s.pct_change_1d()).sum();
+                    let average = total / updated_stocks.len() as f64;
 
-            if !updated_stocks.is_empty() {
-                let total: f64 = updated_stocks.iter().map(|s| s.pct_change_1d()).sum();
-                let average = total / updated_stocks.len() as f64;
+                    bus_state
+                        .avg_change
+                        .store(average, atomic::Ordering::Release);
+                }
+                for callback in updated_callbacks.borrow().iter() {
+                    callback();
+                }
 
-                bus_state
-                    .avg_change
-                    .store(average, atomic::Ordering::Release);
+                if !refresh_pending.replace(false) {
+                    break;
+                }
+            }
+
+            refreshing.set(false);
+            refresh_spinner.set_spinning(false);
+            if let Ok(time) = glib::DateTime::now_local().and_then(|now| now.format("%H:%M:%S")) {
+                updated_label.set_text(&format!("Updated {}", time));
             }
         });
     }
 
+    /// Totals of the holdings and how many stocks are up or down today, kept current
+    /// after every refresh and whenever stocks are added or removed
+    pub fn create_summary_bar(&self) -> Box {
+        let value_label = Label::new(None);
+        let day_label = Label::new(None);
+        let unrealized_label = Label::new(None);
+        let breadth_label = Label::builder().hexpand(true).xalign(1.0).build();
+
+        let container = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(18)
+            .margin_start(12)
+            .margin_end(12)
+            .css_classes(["numeric"])
+            .build();
+        container.append(&value_label);
+        container.append(&day_label);
+        container.append(&unrealized_label);
+        container.append(&breadth_label);
+
+        let update = glib::clone!(
+            #[weak(rename_to = stocks)]
+            self.stocks,
+            move || {
+                let stocks: Vec<StockObject> = stocks
+                    .iter::<StockObject>()
+                    .filter_map(Result::ok)
+                    .collect();
+                let summary = PortfolioSummary::compute(&stocks);
+
+                for label in [&value_label, &day_label, &unrealized_label] {
+                    label.set_visible(summary.holdings > 0);
+                }
+                value_label.set_text(&format!("Value {:.2}", summary.value));
+                day_label.set_text(&format!(
+                    "Day {:+.2} ({:+.2}%)",
+                    summary.day_change,
+                    summary.day_pct * 100.0
+                ));
+                set_direction_class(&day_label, summary.day_change);
+                unrealized_label.set_text(&format!(
+                    "Unrealized {:+.2} ({:+.2}%)",
+                    summary.unrealized,
+                    summary.unrealized_pct * 100.0
+                ));
+                set_direction_class(&unrealized_label, summary.unrealized);
+                breadth_label
+                    .set_text(&format!("▲ {}  ▼ {}", summary.advancers, summary.decliners));
+            }
+        );
+        self.connect_updated(update.clone());
+
+        // Holdings also change in place, e.g. on import or when the config file is edited,
+        // so every stock's are followed and the handlers move along with the list
+        let handlers: Rc<RefCell<Vec<(StockObject, SignalHandlerId)>>> = Rc::default();
+        let follow_holdings = move |stocks: &ListStore| {
+            for (stock, handler) in handlers.take() {
+                stock.disconnect(handler);
+            }
+            for stock in stocks.iter::<StockObject>().filter_map(Result::ok) {
+                for property in ["quantity", "cost"] {
+                    let update = update.clone();
+                    let handler = stock.connect_notify_local(Some(property), move |_, _| update());
+                    handlers.borrow_mut().push((stock.clone(), handler));
+                }
+            }
+            update();
+        };
+        follow_holdings(&self.stocks);
+        self.stocks
+            .connect_items_changed(move |stocks, _, _, _| follow_holdings(stocks));
+        container
+    }
+
+    /// A refresh button with a spinner while prices are fetched and the time of the last
+    /// update
+    pub fn create_refresh_controls(&self) -> Box {
+        let button = Button::builder()
+            .icon_name("view-refresh-symbolic")
+            .action_name("app.refresh")
+            .tooltip_text("Refresh prices (Ctrl+R)")
+            .build();
+
+        let container = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .margin_top(12)
+            .margin_end(12)
+            .valign(Align::Start)
+            .build();
+        container.append(&self.updated_label);
+        container.append(&self.refresh_spinner);
+        container.append(&button);
+        container
+    }
+
     pub fn create_stock_list(&self) -> ScrolledWindow {
-        let selection_model = SingleSelection::new(Some(self.sorted_stocks.clone()));
-        let column_view = ColumnView::new(Some(selection_model));
-        column_view.set_reorderable(false);
+        // Several rows can be selected to compare them, the other actions take one
+        let selection_model = MultiSelection::new(Some(self.sorted_stocks.clone()));
+        let column_view = ColumnView::new(Some(selection_model.clone()));
+        column_view.set_reorderable(true);
+        self.column_view.set(Some(&column_view));
 
         // --- Action Setup ---
         let action_group = SimpleActionGroup::new();
@@ -125,133 +576,342 @@ impl StockManager {
         remove_stock_action.connect_activate(glib::clone!(
             #[weak(rename_to = stocks)]
             self.stocks,
-            #[weak(rename_to = sorted_stocks)]
-            self.sorted_stocks,
+            #[weak(rename_to = tags)]
+            self.tags,
+            #[weak(rename_to = tag_filter)]
+            self.tag_filter,
+            #[strong(rename_to = toast)]
+            self.toast,
             #[weak]
             column_view,
             move |_, _| {
-                let selection = column_view
-                    .model()
-                    .and_downcast::<SingleSelection>()
-                    .unwrap();
-                let view_pos = selection.selected();
-
-                if view_pos != INVALID_LIST_POSITION {
-                    // Find the item in the sorted view
-                    if let Some(item) = sorted_stocks.item(view_pos) {
-                        // Find where this specific object lives in the underlying store
-                        let mut source_pos = None;
-                        for i in 0..stocks.n_items() {
-                            if stocks.item(i).as_ref() == Some(&item) {
-                                source_pos = Some(i);
-                                break;
+                let Some(stock) = selected_stock(&column_view) else {
+                    return;
+                };
+                // Where the object lives in the underlying store, to put it back on undo
+                let Some(pos) = stocks.find(&stock) else {
+                    return;
+                };
+                stocks.remove(pos);
+                refresh_tags(&stocks, &tags, &tag_filter);
+
+                // Only saved once the toast is gone, until then undo just puts the object
+                // back, with its notes and holdings
+                toast.show_with_action(
+                    &format!("Removed {}", stock.ticker()),
+                    "Undo",
+                    glib::clone!(
+                        #[weak]
+                        stocks,
+                        #[weak]
+                        tags,
+                        #[weak]
+                        tag_filter,
+                        #[strong]
+                        toast,
+                        move || {
+                            // It may have been added again in the meantime
+                            if !contains_ticker(&stocks, &stock.ticker()) {
+                                stocks.insert(pos.min(stocks.n_items()), &stock);
                             }
+                            refresh_tags(&stocks, &tags, &tag_filter);
+                            // Another save may have happened while the toast was up
+                            save_store(&stocks, &toast);
                         }
+                    ),
+                    glib::clone!(
+                        #[weak]
+                        stocks,
+                        #[strong]
+                        toast,
+                        move || save_store(&stocks, &toast)
+                    ),
+                );
+            }
+        ));
+        action_group.add_action(&remove_stock_action);
 
-                        // Remove it from the store
-                        if let Some(pos) = source_pos {
-                            stocks.remove(pos);
-
-                            let tickers: Vec<(String, String)> = (0..stocks.n_items())
-                                .filter_map(|i| stocks.item(i))
-                                .map(|obj| {
-                                    let stock = obj.downcast::<StockObject>().unwrap();
-                                    (stock.ticker(), stock.name())
-                                })
-                                .collect();
+        let edit_stock_action = SimpleAction::new("edit", None);
+        edit_stock_action.connect_activate(glib::clone!(
+            #[weak(rename_to = stocks)]
+            self.stocks,
+            #[weak(rename_to = tags)]
+            self.tags,
+            #[weak(rename_to = tag_filter)]
+            self.tag_filter,
+            #[strong(rename_to = toast)]
+            self.toast,
+            #[weak]
+            column_view,
+            move |_, _| {
+                let Some(stock) = selected_stock(&column_view) else {
+                    return;
+                };
 
-                            save_tickers(tickers);
+                let parent = column_view.root().and_downcast::<Window>();
+                show_edit_dialog(
+                    parent.as_ref(),
+                    &stock,
+                    glib::clone!(
+                        #[strong]
+                        toast,
+                        move || {
+                            refresh_tags(&stocks, &tags, &tag_filter);
+                            save_store(&stocks, &toast);
                         }
-                    }
+                    ),
+                );
+            }
+        ));
+        action_group.add_action(&edit_stock_action);
+
+        let compare_action = SimpleAction::new("compare", None);
+        compare_action.connect_activate(glib::clone!(
+            #[strong(rename_to = api)]
+            self.api,
+            #[weak]
+            column_view,
+            move |_, _| {
+                let tickers: Vec<String> = selected_stocks(&column_view)
+                    .iter()
+                    .map(|stock| stock.ticker())
+                    .collect();
+                if tickers.is_empty() {
+                    return;
                 }
+                let parent = column_view.root().and_downcast::<Window>();
+                show_compare_window(parent.as_ref(), tickers, api.clone());
             }
         ));
-        action_group.add_action(&remove_stock_action);
+        action_group.add_action(&compare_action);
+
+        // Removing or editing several rows at once isn't supported, so those actions are
+        // only available for a single selected row
+        let update_actions = move |selection: &MultiSelection| {
+            let count = selection.selection().size();
+            remove_stock_action.set_enabled(count == 1);
+            edit_stock_action.set_enabled(count == 1);
+            compare_action.set_enabled(count > 0);
+        };
+        update_actions(&selection_model);
+        selection_model.connect_selection_changed(move |selection, _, _| update_actions(selection));
         column_view.insert_action_group("stock", Some(&action_group));
 
+        // Only while the list has focus, so Delete keeps working in text entries
+        let shortcuts = ShortcutController::new();
+        shortcuts.add_shortcut(Shortcut::new(
+            ShortcutTrigger::parse_string("Delete"),
+            Some(NamedAction::new("stock.remove")),
+        ));
+        column_view.add_controller(shortcuts);
+
+        // Double-click or Enter on a row opens its chart
+        column_view.connect_activate(glib::clone!(
+            #[strong(rename_to = api)]
+            self.api,
+            move |column_view, position| {
+                let Some(stock) = column_view
+                    .model()
+                    .and_then(|model| model.item(position))
+                    .and_downcast::<StockObject>()
+                else {
+                    return;
+                };
+                let parent = column_view.root().and_downcast::<Window>();
+                show_detail_window(parent.as_ref(), &stock, api.clone());
+            }
+        ));
+
         // --- Menu UI Setup ---
         let menu_model = Menu::new();
+        menu_model.append(Some("Edit…"), Some("stock.edit"));
+        menu_model.append(Some("Compare…"), Some("stock.compare"));
         menu_model.append(Some("Remove"), Some("stock.remove"));
         let popover = PopoverMenu::from_model_full(&menu_model, PopoverMenuFlags::NESTED);
         popover.set_parent(&column_view);
         popover.set_has_arrow(false);
 
+        let columns_popover = self.create_columns_menu(&column_view);
+
         // --- Right Click Gesture ---
         let gesture = GestureClick::new();
         gesture.set_button(3);
         gesture.connect_pressed(glib::clone!(
             #[weak]
             popover,
+            #[weak]
+            columns_popover,
+            #[weak]
+            column_view,
             move |_, _, x, y| {
+                // The header row gets the column menu, everything else the stock menu
+                let on_header = column_view
+                    .pick(x, y, PickFlags::DEFAULT)
+                    .is_some_and(|widget| is_in_header(&widget));
+                let popover = if on_header { columns_popover } else { popover };
+
                 popover.set_pointing_to(Some(&Rectangle::new(x as i32, y as i32, 0, 0)));
                 popover.popup();
             }
         ));
         column_view.add_controller(gesture);
 
+        let mut columns = vec![];
+
         // --- Column 1: Ticker ---
         let factory_ticker = SignalListItemFactory::new();
         factory_ticker.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
             let label = Label::new(None);
             label.set_halign(Align::Start);
             list_item.set_child(Some(&label));
         });
         factory_ticker.connect_bind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
             let stock = list_item.item().and_downcast::<StockObject>().unwrap();
             let label = list_item.child().and_downcast::<Label>().unwrap();
             label.set_text(&stock.ticker());
+            bind_notes_tooltip(&stock, &label);
         });
-        let col_ticker = ColumnViewColumn::new(Some("Ticker"), Some(factory_ticker));
-        column_view.append_column(&col_ticker);
+        let col_ticker = new_column("ticker", factory_ticker);
+        col_ticker.set_sorter(Some(&text_sorter(|s| s.ticker())));
+        columns.push(col_ticker);
 
         // --- Column 2: Name ---
         let factory_name = SignalListItemFactory::new();
         factory_name.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
             let label = Label::new(None);
             label.set_halign(Align::Start);
             label.set_ellipsize(EllipsizeMode::End);
             list_item.set_child(Some(&label));
         });
         factory_name.connect_bind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
             let stock = list_item.item().and_downcast::<StockObject>().unwrap();
             let label = list_item.child().and_downcast::<Label>().unwrap();
-            stock
-                .bind_property("name", &label, "label")
-                .sync_create()
-                .build();
+            label.set_text(&stock.title());
+            // The title falls back to the name, so a rename shows up too
+            let handlers = ["name", "display-name"].map(|property| {
+                stock.connect_notify_local(
+                    Some(property),
+                    glib::clone!(
+                        #[weak]
+                        label,
+                        move |stock, _| label.set_text(&stock.title())
+                    ),
+                )
+            });
+            bind_notes_tooltip(&stock, &label);
+            // SAFETY: Only ever stored and taken as this type, see the unbind below
+            unsafe { label.set_data(TITLE_HANDLERS, (stock, handlers)) };
+        });
+        factory_name.connect_unbind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let label = list_item.child().and_downcast::<Label>().unwrap();
+            // SAFETY: Set in the bind above
+            let bound =
+                unsafe { label.steal_data::<(StockObject, [SignalHandlerId; 2])>(TITLE_HANDLERS) };
+            if let Some((stock, handlers)) = bound {
+                for handler in handlers {
+                    stock.disconnect(handler);
+                }
+            }
         });
-        let col_name = ColumnViewColumn::new(Some("Stock"), Some(factory_name));
+        let col_name = new_column("name", factory_name);
+        col_name.set_sorter(Some(&text_sorter(|s| s.title().to_lowercase())));
         col_name.set_expand(true);
-        column_view.append_column(&col_name);
+        columns.push(col_name);
+
+        // --- Sparkline ---
+        let factory_sparkline = SignalListItemFactory::new();
+        factory_sparkline.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            list_item.set_child(Some(&create_sparkline()));
+        });
+        factory_sparkline.connect_bind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let stock = list_item.item().and_downcast::<StockObject>().unwrap();
+            let area = list_item.child().and_downcast::<DrawingArea>().unwrap();
+            bind_sparkline(&area, &stock);
+        });
+        factory_sparkline.connect_unbind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let area = list_item.child().and_downcast::<DrawingArea>().unwrap();
+            unbind_sparkline(&area);
+        });
+        let col_sparkline = new_column("sparkline", factory_sparkline);
+        col_sparkline.set_sorter(Some(&numeric_sorter(|s| s.pct_change_1d())));
+        columns.push(col_sparkline);
 
         // --- Column 3: Price ---
         let factory_price = SignalListItemFactory::new();
         factory_price.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
             let label = Label::new(None);
             label.set_halign(Align::End);
             list_item.set_child(Some(&label));
         });
-        factory_price.connect_bind(|_, list_item| {
-            let stock = list_item.item().and_downcast::<StockObject>().unwrap();
-            let label = list_item.child().and_downcast::<Label>().unwrap();
+        factory_price.connect_bind(glib::clone!(
+            #[weak(rename_to = settings)]
+            self.settings,
+            move |_, list_item| {
+                let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+                let stock = list_item.item().and_downcast::<StockObject>().unwrap();
+                let label = list_item.child().and_downcast::<Label>().unwrap();
 
-            stock
-                .bind_property("price", &label, "label")
-                .transform_to(|_, value: f64| Some(format!("{:.2}", value)))
-                .sync_create()
-                .build();
+                stock
+                    .bind_property("price", &label, "label")
+                    .transform_to(|_, value: f64| Some(format!("{:.2}", value)))
+                    .sync_create()
+                    .build();
+
+                let previous = Cell::new(stock.price());
+                let handler = stock.connect_notify_local(
+                    Some("price"),
+                    glib::clone!(
+                        #[weak]
+                        settings,
+                        #[weak]
+                        label,
+                        move |stock, _| {
+                            let price = stock.price();
+                            let before = previous.replace(price);
+                            // The first quote of a new row isn't a move
+                            if settings.flash_changes() && before > 0.0 && price != before {
+                                flash_row(&label, price > before);
+                            }
+                        }
+                    ),
+                );
+                // SAFETY: Only ever stored and taken as this type, see the unbind below
+                unsafe { label.set_data(FLASH_HANDLER, (stock, handler)) };
+            }
+        ));
+        factory_price.connect_unbind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let label = list_item.child().and_downcast::<Label>().unwrap();
+            // SAFETY: Set in the bind above
+            let bound =
+                unsafe { label.steal_data::<(StockObject, SignalHandlerId)>(FLASH_HANDLER) };
+            if let Some((stock, handler)) = bound {
+                stock.disconnect(handler);
+            }
         });
-        let col_price = ColumnViewColumn::new(Some("Price"), Some(factory_price));
-        column_view.append_column(&col_price);
+        let col_price = new_column("price", factory_price);
+        col_price.set_sorter(Some(&numeric_sorter(|s| s.price())));
+        columns.push(col_price);
 
         // --- Column 4: 1W Change ---
         let factory_change_1w = SignalListItemFactory::new();
         factory_change_1w.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
             let label = Label::new(None);
             label.set_halign(Align::End);
             list_item.set_child(Some(&label));
         });
         factory_change_1w.connect_bind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
             let stock = list_item.item().and_downcast::<StockObject>().unwrap();
             let label = list_item.child().and_downcast::<Label>().unwrap();
 
@@ -282,17 +942,20 @@ impl StockManager {
                 ),
             );
         });
-        let col_change_1w = ColumnViewColumn::new(Some("Change (1w)"), Some(factory_change_1w));
-        column_view.append_column(&col_change_1w);
+        let col_change_1w = new_column("change_1w", factory_change_1w);
+        col_change_1w.set_sorter(Some(&numeric_sorter(|s| s.pct_change_1w())));
+        columns.push(col_change_1w);
 
         // --- Column 4: 1d Change ---
         let factory_change_1d = SignalListItemFactory::new();
         factory_change_1d.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
             let label = Label::new(None);
             label.set_halign(Align::End);
             list_item.set_child(Some(&label));
         });
         factory_change_1d.connect_bind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
             let stock = list_item.item().and_downcast::<StockObject>().unwrap();
             let label = list_item.child().and_downcast::<Label>().unwrap();
 
@@ -323,140 +986,1174 @@ impl StockManager {
                 ),
             );
         });
-        let col_change_1d = ColumnViewColumn::new(Some("Change (1d)"), Some(factory_change_1d));
-        column_view.append_column(&col_change_1d);
+        let col_change_1d = new_column("change_1d", factory_change_1d);
+        col_change_1d.set_sorter(Some(&numeric_sorter(|s| s.pct_change_1d())));
+        columns.push(col_change_1d);
+
+        // --- Column 6: Volume ---
+        let factory_volume = SignalListItemFactory::new();
+        factory_volume.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let label = Label::new(None);
+            label.set_halign(Align::End);
+            list_item.set_child(Some(&label));
+        });
+        factory_volume.connect_bind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let stock = list_item.item().and_downcast::<StockObject>().unwrap();
+            let label = list_item.child().and_downcast::<Label>().unwrap();
+
+            let binding = stock
+                .bind_property("volume", &label, "label")
+                .transform_to(|_, value: f64| Some(format_compact(value)))
+                .sync_create()
+                .build();
+            // SAFETY: Only ever stored and taken as this type, see `unbind_cell`
+            unsafe { label.set_data(CELL_BINDINGS, vec![binding]) };
+        });
+        factory_volume.connect_unbind(unbind_cell);
+        let col_volume = new_column("volume", factory_volume);
+        col_volume.set_sorter(Some(&numeric_sorter(|s| s.volume())));
+        columns.push(col_volume);
+
+        // --- Column 7: Market Cap ---
+        let factory_market_cap = SignalListItemFactory::new();
+        factory_market_cap.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let label = Label::new(None);
+            label.set_halign(Align::End);
+            list_item.set_child(Some(&label));
+        });
+        factory_market_cap.connect_bind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let stock = list_item.item().and_downcast::<StockObject>().unwrap();
+            let label = list_item.child().and_downcast::<Label>().unwrap();
+
+            let binding = stock
+                .bind_property("market-cap", &label, "label")
+                .transform_to(|_, value: f64| Some(format_compact(value)))
+                .sync_create()
+                .build();
+            // SAFETY: Only ever stored and taken as this type, see `unbind_cell`
+            unsafe { label.set_data(CELL_BINDINGS, vec![binding]) };
+        });
+        factory_market_cap.connect_unbind(unbind_cell);
+        let col_market_cap = new_column("market_cap", factory_market_cap);
+        col_market_cap.set_sorter(Some(&numeric_sorter(|s| s.market_cap())));
+        columns.push(col_market_cap);
+
+        // --- Column 8: Holdings Value ---
+        let factory_value = SignalListItemFactory::new();
+        factory_value.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let label = Label::new(None);
+            label.set_halign(Align::End);
+            list_item.set_child(Some(&label));
+        });
+        factory_value.connect_bind(|_, list_item| {
+            let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+            let stock = list_item.item().and_downcast::<StockObject>().unwrap();
+            let label = list_item.child().and_downcast::<Label>().unwrap();
+
+            // Depends on both price and quantity, so it can't be a single property binding
+            let update = |s: &StockObject, label: &Label| {
+                if s.quantity() > 0.0 {
+                    label.set_text(&format!("{:.2}", s.value()));
+                } else {
+                    label.set_text("");
+                }
+            };
+            update(&stock, &label);
+            let handlers: Vec<SignalHandlerId> = ["price", "quantity"]
+                .into_iter()
+                .map(|property| {
+                    stock.connect_notify_local(
+                        Some(property),
+                        glib::clone!(
+                            #[weak]
+                            label,
+                            move |s, _| update(s, &label)
+                        ),
+                    )
+                })
+                .collect();
+            // SAFETY: Only ever stored and taken as this type, see `unbind_cell`
+            unsafe { label.set_data(CELL_HANDLERS, (stock, handlers)) };
+        });
+        factory_value.connect_unbind(unbind_cell);
+        let col_value = new_column("value", factory_value);
+        col_value.set_sorter(Some(&numeric_sorter(|s| s.value())));
+        columns.push(col_value);
+
+        self.setup_reordering(&columns);
+        self.setup_columns(&column_view, columns);
+        self.setup_sorting(&column_view);
+        self.setup_sections(&column_view);
+        apply_visible_columns(&column_view, &self.settings.columns());
+        self.settings.connect_notify_local(
+            Some("columns"),
+            glib::clone!(
+                #[weak]
+                column_view,
+                move |settings, _| apply_visible_columns(&column_view, &settings.columns())
+            ),
+        );
 
         ScrolledWindow::builder().child(&column_view).build()
     }
 
-    pub fn create_search_bar(&self) -> Box {
+    /// Adds `columns` in the saved order with their saved widths, and keeps the settings
+    /// up to date as columns are dragged around or resized
+    fn setup_columns(&self, column_view: &ColumnView, mut columns: Vec<ColumnViewColumn>) {
+        let order = self.settings.column_order();
+        let position = |column: &ColumnViewColumn| {
+            let id = column.id().map(|id| id.to_string()).unwrap_or_default();
+            // Columns missing from the saved order, e.g. newly added ones, go last
+            order.iter().position(|o| *o == id).unwrap_or(usize::MAX)
+        };
+        columns.sort_by_key(position);
+
+        for column in &columns {
+            column.set_resizable(true);
+            let id = column.id().map(|id| id.to_string()).unwrap_or_default();
+            if let Some(width) = self.settings.column_width(&id) {
+                column.set_fixed_width(width);
+            }
+
+            column.connect_fixed_width_notify(glib::clone!(
+                #[weak(rename_to = settings)]
+                self.settings,
+                move |column| settings.set_column_width(&id, column.fixed_width())
+            ));
+            column_view.append_column(column);
+        }
+
+        column_view.columns().connect_items_changed(glib::clone!(
+            #[weak(rename_to = settings)]
+            self.settings,
+            move |columns, _, _, _| {
+                let order: Vec<String> = (0..columns.n_items())
+                    .filter_map(|i| columns.item(i).and_downcast::<ColumnViewColumn>())
+                    .filter_map(|column| column.id().map(|id| id.to_string()))
+                    .collect();
+                if settings.column_order() != order {
+                    settings.set_column_order(order);
+                }
+            }
+        ));
+    }
+
+    /// A menu with a checkbox per column, shown when right-clicking the header
+    fn create_columns_menu(&self, column_view: &ColumnView) -> PopoverMenu {
+        let action_group = SimpleActionGroup::new();
+        let menu_model = Menu::new();
+
+        for (id, title) in COLUMNS {
+            let visible = self.settings.columns().iter().any(|c| c == id);
+            let action = SimpleAction::new_stateful(id, None, &visible.to_variant());
+
+            action.connect_activate(glib::clone!(
+                #[weak(rename_to = settings)]
+                self.settings,
+                move |action, _| {
+                    let visible = !action
+                        .state()
+                        .and_then(|s| s.get::<bool>())
+                        .unwrap_or(false);
+                    let mut columns = settings.columns();
+                    columns.retain(|c| c != id);
+                    if visible {
+                        columns.push(id.to_string());
+                    }
+                    settings.set_columns(columns);
+                }
+            ));
+            action_group.add_action(&action);
+            menu_model.append(Some(title), Some(&format!("columns.{}", id)));
+        }
+
+        // Keep the checkboxes in sync, the preferences window changes the same setting
+        self.settings.connect_notify_local(
+            Some("columns"),
+            glib::clone!(
+                #[weak]
+                action_group,
+                move |settings, _| {
+                    let visible = settings.columns();
+                    for (id, _) in COLUMNS {
+                        action_group
+                            .change_action_state(id, &visible.iter().any(|c| c == id).to_variant());
+                    }
+                }
+            ),
+        );
+
+        let manual_action = SimpleAction::new_stateful(
+            "manual-sort",
+            None,
+            &self.settings.sort_column().is_empty().to_variant(),
+        );
+        manual_action.connect_activate(glib::clone!(
+            #[weak]
+            column_view,
+            move |_, _| column_view.sort_by_column(None, SortType::Ascending)
+        ));
+        self.settings.connect_notify_local(
+            Some("sort-column"),
+            glib::clone!(
+                #[weak]
+                manual_action,
+                move |settings, _| {
+                    manual_action.set_state(&settings.sort_column().is_empty().to_variant())
+                }
+            ),
+        );
+        action_group.add_action(&manual_action);
+
+        let group_action = SimpleAction::new_stateful(
+            "group-by",
+            Some(glib::VariantTy::STRING),
+            &self.settings.group_by().to_variant(),
+        );
+        group_action.connect_activate(glib::clone!(
+            #[weak(rename_to = settings)]
+            self.settings,
+            move |_, target| {
+                if let Some(group_by) = target.and_then(|t| t.get::<String>()) {
+                    settings.set_group_by(group_by);
+                }
+            }
+        ));
+        self.settings.connect_notify_local(
+            Some("group-by"),
+            glib::clone!(
+                #[weak]
+                group_action,
+                move |settings, _| group_action.set_state(&settings.group_by().to_variant())
+            ),
+        );
+        action_group.add_action(&group_action);
+
+        let group_menu = Menu::new();
+        for (id, title) in GROUPS {
+            let item = MenuItem::new(Some(title), None);
+            item.set_action_and_target_value(Some("columns.group-by"), Some(&id.to_variant()));
+            group_menu.append_item(&item);
+        }
+
+        let sort_section = Menu::new();
+        sort_section.append(Some("Manual Order"), Some("columns.manual-sort"));
+        sort_section.append_submenu(Some("Group By"), &group_menu);
+        menu_model.append_section(None, &sort_section);
+
+        column_view.insert_action_group("columns", Some(&action_group));
+
+        let popover = PopoverMenu::from_model(Some(&menu_model));
+        popover.set_parent(column_view);
+        popover.set_has_arrow(false);
+        popover
+    }
+
+    /// Lets rows be dragged onto other rows to move them there, while the list is in
+    /// manual order
+    fn setup_reordering(&self, columns: &[ColumnViewColumn]) {
+        let factories = columns
+            .iter()
+            .filter_map(|column| column.factory().and_downcast::<SignalListItemFactory>());
+
+        // Runs after the setup handler of the column, so every cell already has its child
+        for factory in factories {
+            factory.connect_setup(glib::clone!(
+                #[weak(rename_to = stocks)]
+                self.stocks,
+                #[weak(rename_to = settings)]
+                self.settings,
+                #[strong(rename_to = toast)]
+                self.toast,
+                move |_, list_item| {
+                    let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+                    let Some(cell) = list_item.child() else {
+                        return;
+                    };
+
+                    let drag_source = DragSource::new();
+                    drag_source.set_actions(DragAction::MOVE);
+                    drag_source.connect_prepare(glib::clone!(
+                        #[weak]
+                        list_item,
+                        #[weak]
+                        settings,
+                        #[upgrade_or]
+                        None,
+                        move |_, _, _| {
+                            if !settings.sort_column().is_empty() {
+                                return None;
+                            }
+                            let stock = list_item.item().and_downcast::<StockObject>()?;
+                            Some(ContentProvider::for_value(&stock.ticker().to_value()))
+                        }
+                    ));
+                    cell.add_controller(drag_source);
+
+                    let drop_target = DropTarget::new(String::static_type(), DragAction::MOVE);
+                    drop_target.connect_drop(glib::clone!(
+                        #[weak]
+                        list_item,
+                        #[weak]
+                        stocks,
+                        #[strong]
+                        toast,
+                        #[upgrade_or]
+                        false,
+                        move |target, value, _, y| {
+                            let (Ok(ticker), Some(target_stock)) = (
+                                value.get::<String>(),
+                                list_item.item().and_downcast::<StockObject>(),
+                            ) else {
+                                return false;
+                            };
+                            // The lower half of a row drops below it
+                            let after =
+                                target.widget().is_some_and(|w| y > w.height() as f64 / 2.0);
+
+                            let moved = move_stock(&stocks, &ticker, &target_stock, after);
+                            if moved {
+                                save_store(&stocks, &toast);
+                            }
+                            moved
+                        }
+                    ));
+                    cell.add_controller(drop_target);
+                }
+            ));
+        }
+    }
+
+    /// Splits the list into sections with a header showing the subtotals of each, when
+    /// grouping is turned on in the settings
+    fn setup_sections(&self, column_view: &ColumnView) {
+        let section_sorter = CustomSorter::new(glib::clone!(
+            #[weak(rename_to = settings)]
+            self.settings,
+            #[upgrade_or]
+            Ordering::Equal.into(),
+            move |a, b| {
+                let group_by = settings.group_by();
+                let key = |obj: &glib::Object| {
+                    group_key(obj.downcast_ref::<StockObject>().unwrap(), &group_by)
+                };
+                let (a, b) = (key(a), key(b));
+                // Stocks without a group go last
+                (a.is_none(), a).cmp(&(b.is_none(), b)).into()
+            }
+        ));
+
+        let header_factory = SignalListItemFactory::new();
+        header_factory.connect_setup(glib::clone!(
+            #[weak(rename_to = settings)]
+            self.settings,
+            #[weak(rename_to = sorted_stocks)]
+            self.sorted_stocks,
+            move |_, header| {
+                let header = header.downcast_ref::<ListHeader>().unwrap();
+                let title = Label::builder()
+                    .xalign(0.0)
+                    .hexpand(true)
+                    .css_classes(["heading"])
+                    .build();
+                let subtotal = Label::builder()
+                    .xalign(1.0)
+                    .css_classes(["dim-label", "numeric"])
+                    .build();
+                let row = Box::new(Orientation::Horizontal, 12);
+                row.append(&title);
+                row.append(&subtotal);
+                header.set_child(Some(&row));
+
+                // The subtotal follows the prices of the stocks in the section, so the
+                // handlers move along whenever the header is reused for another section
+                let handlers: Rc<RefCell<Vec<(StockObject, SignalHandlerId)>>> = Rc::default();
+                let rebind = glib::clone!(
+                    #[weak]
+                    settings,
+                    #[weak]
+                    sorted_stocks,
+                    #[weak]
+                    title,
+                    #[weak]
+                    subtotal,
+                    move |header: &ListHeader| {
+                        for (stock, handler) in handlers.take() {
+                            stock.disconnect(handler);
+                        }
+                        let Some(first) = header.item().and_downcast::<StockObject>() else {
+                            return;
+                        };
+                        let group_by = settings.group_by();
+                        title.set_text(&group_key(&first, &group_by).unwrap_or_else(|| {
+                            if group_by == "tag" {
+                                "Untagged".to_string()
+                            } else {
+                                "Unknown".to_string()
+                            }
+                        }));
+
+                        let section = section_stocks(header, &sorted_stocks);
+                        subtotal.set_text(&section_subtotal(&section));
+                        for stock in &section {
+                            for property in ["pct-change-1d", "price", "quantity"] {
+                                let handler = stock.connect_notify_local(
+                                    Some(property),
+                                    glib::clone!(
+                                        #[weak]
+                                        header,
+                                        #[weak]
+                                        sorted_stocks,
+                                        #[weak]
+                                        subtotal,
+                                        move |_, _| {
+                                            let section = section_stocks(&header, &sorted_stocks);
+                                            subtotal.set_text(&section_subtotal(&section));
+                                        }
+                                    ),
+                                );
+                                handlers.borrow_mut().push((stock.clone(), handler));
+                            }
+                        }
+                    }
+                );
+                for property in ["item", "start", "end"] {
+                    header.connect_notify_local(Some(property), {
+                        let rebind = rebind.clone();
+                        move |header, _| rebind(header)
+                    });
+                }
+            }
+        ));
+
+        let apply = glib::clone!(
+            #[weak(rename_to = sorted_stocks)]
+            self.sorted_stocks,
+            #[weak]
+            column_view,
+            move |settings: &AppSettings| {
+                // Unset first so existing headers are rebuilt with the new titles
+                column_view.set_header_factory(None::<&SignalListItemFactory>);
+                if settings.group_by().is_empty() {
+                    sorted_stocks.set_section_sorter(None::<&CustomSorter>);
+                } else {
+                    sorted_stocks.set_section_sorter(Some(&section_sorter));
+                    section_sorter.changed(SorterChange::Different);
+                    column_view.set_header_factory(Some(&header_factory));
+                }
+            }
+        );
+        apply(&self.settings);
+        self.settings
+            .connect_notify_local(Some("group-by"), move |settings, _| apply(settings));
+    }
+
+    /// Sorts the list by whichever column header was clicked last, restoring and saving
+    /// the choice through the settings. Without a sort column the list is in manual order,
+    /// i.e. the order of the store.
+    fn setup_sorting(&self, column_view: &ColumnView) {
+        let Some(sorter) = column_view.sorter().and_downcast::<ColumnViewSorter>() else {
+            return;
+        };
+        self.sorted_stocks.set_sorter(Some(&sorter));
+
+        let order = if self.settings.sort_descending() {
+            SortType::Descending
+        } else {
+            SortType::Ascending
+        };
+        column_view.sort_by_column(
+            find_column(column_view, &self.settings.sort_column()).as_ref(),
+            order,
+        );
+
+        sorter.connect_changed(glib::clone!(
+            #[weak(rename_to = settings)]
+            self.settings,
+            move |sorter, _| {
+                // No sort column is the manual order
+                let id = sorter
+                    .primary_sort_column()
+                    .and_then(|column| column.id())
+                    .map(|id| id.to_string())
+                    .unwrap_or_default();
+                let descending = sorter.primary_sort_order() == SortType::Descending;
+
+                // Refreshes also emit "changed", only save when the sort itself changed
+                if settings.sort_column() != id {
+                    settings.set_sort_column(id);
+                }
+                if settings.sort_descending() != descending {
+                    settings.set_sort_descending(descending);
+                }
+            }
+        ));
+    }
+
+    /// A search bar that narrows the list down with a `QuickFilter`, revealed with
+    /// `set_search_mode`
+    pub fn create_quick_filter(&self) -> SearchBar {
+        let entry = SearchEntry::builder()
+            .placeholder_text("Filter, e.g. apple tag:tech change1d<-2")
+            .tooltip_text(
+                "Matches ticker, name or tags. Also tag:name and comparisons like price>=100, \
+                 with the fields price, change1d, change1w, volume, marketcap, quantity, cost \
+                 and value",
+            )
+            .hexpand(true)
+            .build();
+
+        entry.connect_search_changed(glib::clone!(
+            #[strong(rename_to = quick_filter)]
+            self.quick_filter,
+            #[weak(rename_to = quick_filter_model)]
+            self.quick_filter_model,
+            move |entry| {
+                quick_filter.replace(QuickFilter::parse(&entry.text()));
+                quick_filter_model.changed(FilterChange::Different);
+            }
+        ));
+
+        let search_bar = SearchBar::builder()
+            .child(&entry)
+            .show_close_button(true)
+            .build();
+        search_bar.connect_entry(&entry);
+        search_bar
+    }
+
+    /// A dropdown that narrows the list down to stocks with the chosen tag
+    pub fn create_tag_filter(&self) -> DropDown {
+        let dropdown = DropDown::builder()
+            .model(&self.tags)
+            .margin_top(12)
+            .margin_end(12)
+            .valign(Align::Start)
+            .build();
+
+        dropdown.connect_selected_item_notify(glib::clone!(
+            #[strong(rename_to = selected_tag)]
+            self.selected_tag,
+            #[weak(rename_to = tag_filter)]
+            self.tag_filter,
+            move |dropdown| {
+                // The first entry is "All tags"
+                let tag = (dropdown.selected() > 0)
+                    .then(|| dropdown.selected_item().and_downcast::<StringObject>())
+                    .flatten()
+                    .map(|s| s.string().to_string());
+
+                if *selected_tag.borrow() != tag {
+                    selected_tag.replace(tag);
+                    tag_filter.changed(FilterChange::Different);
+                }
+            }
+        ));
+
+        // Rebuilding the tag list resets the selection, put it back if the tag still exists
+        self.tags.connect_items_changed(glib::clone!(
+            #[strong(rename_to = selected_tag)]
+            self.selected_tag,
+            #[weak]
+            dropdown,
+            move |tags, _, _, _| {
+                let Some(tag) = selected_tag.borrow().clone() else {
+                    return;
+                };
+                let position =
+                    (1..tags.n_items()).find(|&i| tags.string(i).as_deref() == Some(tag.as_str()));
+                dropdown.set_selected(position.unwrap_or(0));
+            }
+        ));
+
+        dropdown
+    }
+
+    /// The search bar for adding tickers, and its entry for the focus shortcut
+    pub fn create_search_bar(&self) -> (Box, SearchEntry) {
         let container = Box::new(Orientation::Vertical, 6);
         let search_entry = SearchEntry::builder()
-            .placeholder_text("Search ticker and press Enter...")
+            .placeholder_text("Search ticker to add...")
+            .search_delay(SEARCH_DELAY_MS)
             .margin_top(12)
             .margin_start(12)
             .margin_end(12)
             .build();
 
+        // Rows can't take focus so typing carries on in the entry while results show
+        let results_list = ListBox::builder()
+            .selection_mode(SelectionMode::Single)
+            .can_focus(false)
+            .build();
+        let results_stack = Stack::new();
+        results_stack.add_named(
+            &Spinner::builder()
+                .spinning(true)
+                .margin_top(12)
+                .margin_bottom(12)
+                .build(),
+            Some("loading"),
+        );
+        results_stack.add_named(
+            &Label::builder()
+                .label("No results")
+                .css_classes(["dim-label"])
+                .margin_top(12)
+                .margin_bottom(12)
+                .margin_start(12)
+                .margin_end(12)
+                .build(),
+            Some("empty"),
+        );
+        results_stack.add_named(&results_list, Some("results"));
+
         let results_popover = Popover::builder()
-            .autohide(true)
+            .autohide(false)
+            .has_arrow(false)
             .position(PositionType::Bottom)
+            .child(&results_stack)
             .build();
         results_popover.set_parent(&search_entry);
 
-        let results_list = ListBox::new();
-        results_popover.set_child(Some(&results_list));
+        // Symbol and name of each row of `results_list`, and the query they were found for
+        let results: Rc<RefCell<Vec<(String, String)>>> = Rc::default();
+        let results_query: Rc<RefCell<String>> = Rc::default();
+        // Set while a picked result is being fetched, so it can't be added twice
+        let adding = Rc::new(Cell::new(false));
+        // The search in flight, replaced by every new query
+        let search_task: Rc<RefCell<Option<JoinHandle<()>>>> = Rc::default();
 
-        // --- TRIGGER SEARCH ON ENTER ---
-        search_entry.connect_activate(glib::clone!(
-            #[weak(rename_to = api)]
-            self.api,
+        // Emitted once typing pauses for SEARCH_DELAY_MS
+        search_entry.connect_search_changed(glib::clone!(
             #[weak]
             results_popover,
             #[weak]
+            results_stack,
+            #[weak]
             results_list,
+            #[strong]
+            results,
+            #[strong]
+            results_query,
+            #[strong]
+            search_task,
             move |entry| {
-                let text = entry.text().to_string();
-                if text.is_empty() {
+                // Its results would be stale by the time they arrive
+                if let Some(task) = search_task.take() {
+                    task.abort();
+                }
+                results.borrow_mut().clear();
+                results_query.borrow_mut().clear();
+                results_list.remove_all();
+
+                let query = entry.text().trim().to_string();
+                if query.is_empty() {
+                    results_popover.popdown();
                     return;
                 }
+                results_stack.set_visible_child_name("loading");
+                results_popover.popup();
 
-                glib::MainContext::default().spawn_local(glib::clone!(
-                    #[weak]
-                    api,
-                    #[weak]
-                    results_popover,
-                    #[weak]
-                    results_list,
+                let task = glib::MainContext::default().spawn_local(glib::clone!(
+                    #[strong]
+                    results,
+                    #[strong]
+                    results_query,
                     async move {
-                        // TODO:
-                        // Create a wrapper inside api that handles the tokio runtime so we don't
-                        // have to construct a new rt every time we use the api
-                        let rt = tokio::runtime::Builder::new_current_thread()
-                            .enable_all()
-                            .build()
-                            .unwrap();
-
-                        let results = rt.block_on(async move { api.search_ticker(&text).await });
-
-                        // Clear old results
-                        while let Some(child) = results_list.first_child() {
-                            results_list.remove(&child);
-                        }
-
-                        if results.is_empty() {
-                            results_popover.popdown();
-                            return;
-                        }
-
-                        for (symbol, name) in results {
-                            let symbol = glib::markup_escape_text(&symbol);
-                            let name = glib::markup_escape_text(&name);
+                        let searched = query.clone();
+                        // Searches on a worker thread so the entry stays responsive
+                        let found = gio::spawn_blocking(move || {
+                            tokio::runtime::Builder::new_current_thread()
+                                .enable_all()
+                                .build()
+                                .unwrap()
+                                .block_on(search_tickers(&query))
+                        })
+                        .await
+                        .unwrap_or_default();
 
+                        results_list.remove_all();
+                        for (symbol, name) in &found {
                             let label = Label::builder()
-                                .label(format!("<b>{}</b> - {}", symbol, name))
+                                .label(format!(
+                                    "<b>{}</b> - {}",
+                                    glib::markup_escape_text(symbol),
+                                    glib::markup_escape_text(name)
+                                ))
                                 .use_markup(true)
                                 .xalign(0.0)
                                 .build();
-
-                            let row = ListBoxRow::new();
-                            row.set_child(Some(&label));
-
-                            unsafe {
-                                row.set_data("ticker_symbol", symbol.to_string());
-                                row.set_data("stock_name", name.to_string());
-                            }
-
-                            results_list.append(&row);
+                            results_list.append(
+                                &ListBoxRow::builder().child(&label).can_focus(false).build(),
+                            );
                         }
-                        results_popover.popup();
+                        results_stack.set_visible_child_name(if found.is_empty() {
+                            "empty"
+                        } else {
+                            "results"
+                        });
+                        results.replace(found);
+                        results_query.replace(searched);
                     }
                 ));
+                search_task.replace(Some(task));
             }
         ));
 
-        // --- SELECTION LOGIC ---
-        results_list.connect_row_activated(glib::clone!(
+        // Adds a result once it's known to return prices, a ticker that's already on the
+        // list is brought into view instead
+        let add_result = glib::clone!(
+            #[strong(rename_to = api)]
+            self.api,
             #[weak(rename_to = model)]
             self.stocks,
+            #[strong(rename_to = toast)]
+            self.toast,
+            #[strong(rename_to = column_view)]
+            self.column_view,
             #[weak]
             results_popover,
             #[weak]
+            results_stack,
+            #[weak]
             search_entry,
-            move |_, row| {
-                // SAFETY:
-                // We set these exact two values above
-                let symbol: String = unsafe {
-                    row.data::<String>("ticker_symbol")
-                        .map(|s| s.as_ref().clone())
-                        .unwrap_or_default()
-                };
-                let stock_name: String = unsafe {
-                    row.data::<String>("stock_name")
-                        .map(|s| s.as_ref().clone())
-                        .unwrap_or_default()
+            #[strong]
+            results,
+            #[strong]
+            adding,
+            move |index: usize| {
+                if adding.get() {
+                    return;
+                }
+                let Some((symbol, name)) = results.borrow().get(index).cloned() else {
+                    return;
                 };
 
-                if !symbol.is_empty() {
-                    model.append(&StockObject::new(&symbol, &stock_name));
+                if contains_ticker(&model, &symbol) {
+                    search_entry.set_text("");
+                    results_popover.popdown();
+                    let shown = column_view
+                        .upgrade()
+                        .is_some_and(|column_view| focus_row(&column_view, &symbol));
+                    if !shown {
+                        toast.show(&format!("{} is already in the watchlist", symbol));
+                    }
+                    return;
+                }
 
-                    let tickers: Vec<(String, String)> = (0..model.n_items())
-                        .filter_map(|i| model.item(i))
-                        .map(|obj| {
-                            let stock = obj.downcast::<StockObject>().unwrap();
-                            (stock.ticker(), stock.name())
-                        })
-                        .collect();
+                adding.set(true);
+                results_stack.set_visible_child_name("loading");
+                glib::MainContext::default().spawn_local(glib::clone!(
+                    #[strong]
+                    api,
+                    #[strong]
+                    toast,
+                    #[strong]
+                    adding,
+                    async move {
+                        // Let the spinner show before the fetch blocks the main loop
+                        glib::timeout_future(Duration::ZERO).await;
+                        let rt = tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build()
+                            .unwrap();
+
+                        let fetched = rt.block_on(api.daily_range(&symbol));
+                        adding.set(false);
+                        match fetched {
+                            // It may have been added another way in the meantime
+                            Ok(_) if contains_ticker(&model, &symbol) => {
+                                search_entry.set_text("");
+                                results_popover.popdown();
+                            }
+                            Ok(res) => {
+                                let stock = StockObject::new(&symbol, &name);
+                                stock.set_pct_change_1d(res.pct_change);
+                                stock.set_price(res.last_close);
+                                stock.set_volume(res.volume);
+                                stock.set_quote_type(res.instrument_type);
+                                stock.set_exchange(res.exchange);
+                                model.append(&stock);
+                                save_store(&model, &toast);
+
+                                search_entry.set_text("");
+                                results_popover.popdown();
+                            }
+                            Err(_) => {
+                                results_stack.set_visible_child_name("results");
+                                toast.show(&format!("No prices for {}, not added", symbol));
+                            }
+                        }
+                    }
+                ));
+            }
+        );
+
+        results_list.connect_row_activated(glib::clone!(
+            #[strong]
+            add_result,
+            move |_, row| add_result(row.index() as usize)
+        ));
 
-                    save_tickers(tickers);
+        // Enter adds the highlighted result, or the first one, but only once the results for
+        // the current text are in
+        search_entry.connect_activate(glib::clone!(
+            #[weak]
+            results_list,
+            #[weak]
+            results_stack,
+            #[weak]
+            results_popover,
+            move |entry| {
+                let current = results_stack.visible_child_name().as_deref() == Some("results")
+                    && *results_query.borrow() == entry.text().trim();
+                if !results_popover.is_visible() || !current {
+                    return;
+                }
+                let row = results_list
+                    .selected_row()
+                    .or_else(|| results_list.row_at_index(0));
+                if let Some(row) = row {
+                    add_result(row.index() as usize);
                 }
+            }
+        ));
 
-                search_entry.set_text("");
-                results_popover.popdown();
+        // Up and Down move through the results without leaving the entry
+        let keys = EventControllerKey::new();
+        keys.set_propagation_phase(PropagationPhase::Capture);
+        keys.connect_key_pressed(glib::clone!(
+            #[weak]
+            results_list,
+            #[weak]
+            results_popover,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, key, _, _| {
+                if !results_popover.is_visible() {
+                    return glib::Propagation::Proceed;
+                }
+                let current = results_list.selected_row().map_or(-1, |row| row.index());
+                let next = match key {
+                    gdk::Key::Down => current + 1,
+                    gdk::Key::Up => current - 1,
+                    gdk::Key::Escape => {
+                        results_popover.popdown();
+                        return glib::Propagation::Stop;
+                    }
+                    _ => return glib::Propagation::Proceed,
+                };
+                if let Some(row) = results_list.row_at_index(next.max(0)) {
+                    results_list.select_row(Some(&row));
+                }
+                glib::Propagation::Stop
             }
         ));
+        search_entry.add_controller(keys);
+
+        // The popover doesn't hide itself as it never takes the focus
+        let focus = EventControllerFocus::new();
+        focus.connect_leave(glib::clone!(
+            #[weak]
+            results_popover,
+            move |_| results_popover.popdown()
+        ));
+        search_entry.add_controller(focus);
 
         container.append(&search_entry);
-        container
+        (container, search_entry)
+    }
+}
+
+fn new_column(id: &str, factory: SignalListItemFactory) -> ColumnViewColumn {
+    let title = COLUMNS
+        .iter()
+        .find(|(column_id, _)| *column_id == id)
+        .map(|(_, title)| *title);
+
+    let column = ColumnViewColumn::new(title, Some(factory));
+    column.set_id(Some(id));
+    column
+}
+
+/// Gives `label` the `success` class for gains and `error` for losses
+fn set_direction_class(label: &Label, change: f64) {
+    if change >= 0.0 {
+        label.add_css_class("success");
+        label.remove_css_class("error");
+    } else {
+        label.add_css_class("error");
+        label.remove_css_class("success");
+    }
+}
+
+/// Highlights the row holding `label` green when the price went up, red when it went down
+fn flash_row(label: &Label, up: bool) {
+    // Label -> cell -> row
+    let Some(row) = label.parent().and_then(|cell| cell.parent()) else {
+        return;
+    };
+    let (class, other) = if up {
+        ("flash-up", "flash-down")
+    } else {
+        ("flash-down", "flash-up")
+    };
+    row.remove_css_class(other);
+    row.add_css_class(class);
+    glib::timeout_add_local_once(
+        FLASH_DURATION,
+        glib::clone!(
+            #[weak]
+            row,
+            move || row.remove_css_class(class)
+        ),
+    );
+}
+
+/// Selects the row of `ticker` and scrolls to it, false if it's filtered out
+fn focus_row(column_view: &ColumnView, ticker: &str) -> bool {
+    let Some(model) = column_view.model() else {
+        return false;
+    };
+    let position = (0..model.n_items()).find(|i| {
+        model
+            .item(*i)
+            .and_downcast::<StockObject>()
+            .is_some_and(|stock| stock.ticker() == ticker)
+    });
+    match position {
+        Some(position) => {
+            column_view.scroll_to(
+                position,
+                None,
+                ListScrollFlags::FOCUS | ListScrollFlags::SELECT,
+                None,
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// The selected rows, in display order
+fn selected_stocks(column_view: &ColumnView) -> Vec<StockObject> {
+    let Some(model) = column_view.model() else {
+        return vec![];
+    };
+    (0..model.n_items())
+        .filter(|i| model.is_selected(*i))
+        .filter_map(|i| model.item(i).and_downcast::<StockObject>())
+        .collect()
+}
+
+/// The selected row, as long as it's the only one
+fn selected_stock(column_view: &ColumnView) -> Option<StockObject> {
+    match selected_stocks(column_view).as_slice() {
+        [stock] => Some(stock.clone()),
+        _ => None,
+    }
+}
+
+fn is_in_header(widget: &Widget) -> bool {
+    let mut current = Some(widget.clone());
+    while let Some(widget) = current {
+        if widget.css_name() == "header" {
+            return true;
+        }
+        if widget.is::<ColumnView>() {
+            return false;
+        }
+        current = widget.parent();
+    }
+    false
+}
+
+/// Formats large numbers as e.g. 1.23M, empty for zero
+pub fn format_compact(value: f64) -> String {
+    let units = [(1e12, "T"), (1e9, "B"), (1e6, "M"), (1e3, "K")];
+    if value == 0.0 {
+        return String::new();
+    }
+    match units.iter().find(|(size, _)| value.abs() >= *size) {
+        Some((size, suffix)) => format!("{:.2}{}", value / size, suffix),
+        None => format!("{:.0}", value),
+    }
+}
+
+fn find_column(column_view: &ColumnView, id: &str) -> Option<ColumnViewColumn> {
+    let columns = column_view.columns();
+    (0..columns.n_items())
+        .filter_map(|i| columns.item(i).and_downcast::<ColumnViewColumn>())
+        .find(|column| column.id().as_deref() == Some(id))
+}
+
+fn text_sorter(key: fn(&StockObject) -> String) -> CustomSorter {
+    CustomSorter::new(move |a, b| {
+        let stock1 = a.downcast_ref::<StockObject>().unwrap();
+        let stock2 = b.downcast_ref::<StockObject>().unwrap();
+        key(stock1).cmp(&key(stock2)).into()
+    })
+}
+
+fn numeric_sorter(key: fn(&StockObject) -> f64) -> CustomSorter {
+    CustomSorter::new(move |a, b| {
+        let stock1 = a.downcast_ref::<StockObject>().unwrap();
+        let stock2 = b.downcast_ref::<StockObject>().unwrap();
+
+        key(stock1)
+            .partial_cmp(&key(stock2))
+            .unwrap_or(Ordering::Equal)
+            .into()
+    })
+}
+
+fn apply_visible_columns(column_view: &ColumnView, visible: &[String]) {
+    let columns = column_view.columns();
+    for column in
+        (0..columns.n_items()).filter_map(|i| columns.item(i).and_downcast::<ColumnViewColumn>())
+    {
+        let id = column.id().map(|id| id.to_string()).unwrap_or_default();
+        column.set_visible(visible.contains(&id));
+    }
+}
+
+fn store_entries(stocks: &ListStore) -> Vec<TickerEntry> {
+    (0..stocks.n_items())
+        .filter_map(|i| stocks.item(i).and_downcast::<StockObject>())
+        .map(|stock| stock.to_entry())
+        .collect()
+}
+
+/// Shows the notes of `stock` as the tooltip of `label`
+/// Drops what the bind of a numeric column kept on its label, so a recycled row doesn't
+/// keep showing values of the stock it was bound to before
+fn unbind_cell(_: &SignalListItemFactory, list_item: &glib::Object) {
+    let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+    let label = list_item.child().and_downcast::<Label>().unwrap();
+    // SAFETY: Set in the binds of the columns, always as these types
+    if let Some(bindings) = unsafe { label.steal_data::<Vec<Binding>>(CELL_BINDINGS) } {
+        for binding in bindings {
+            binding.unbind();
+        }
+    }
+    // SAFETY: Same as above
+    let handlers =
+        unsafe { label.steal_data::<(StockObject, Vec<SignalHandlerId>)>(CELL_HANDLERS) };
+    if let Some((stock, handlers)) = handlers {
+        for handler in handlers {
+            stock.disconnect(handler);
+        }
+    }
+}
+
+fn bind_notes_tooltip(stock: &StockObject, label: &Label) {
+    stock
+        .bind_property("notes", label, "tooltip-text")
+        .transform_to(|_, notes: String| Some((!notes.is_empty()).then_some(notes)))
+        .sync_create()
+        .build();
+}
+
+/// Rebuilds the tag list from the store and re-runs the tag filter
+fn refresh_tags(stocks: &ListStore, tags: &StringList, tag_filter: &CustomFilter) {
+    let in_use: BTreeSet<String> = (0..stocks.n_items())
+        .filter_map(|i| stocks.item(i).and_downcast::<StockObject>())
+        .flat_map(|stock| stock.tags())
+        .collect();
+
+    let current: Vec<String> = (1..tags.n_items())
+        .filter_map(|i| tags.string(i).map(String::from))
+        .collect();
+
+    if tags.n_items() == 0 || !current.iter().eq(in_use.iter()) {
+        let new_tags: Vec<&str> = std::iter::once("All tags")
+            .chain(in_use.iter().map(String::as_str))
+            .collect();
+        tags.splice(0, tags.n_items(), &new_tags);
+    }
+
+    tag_filter.changed(FilterChange::Different);
+}
+
+/// The section `stock` goes in for `group_by`, `None` if it has no group. Stocks are
+/// grouped by their first tag.
+fn group_key(stock: &StockObject, group_by: &str) -> Option<String> {
+    let key = match group_by {
+        "tag" => stock.tags().into_iter().next().unwrap_or_default(),
+        "type" => match stock.quote_type().as_str() {
+            "EQUITY" => "Stocks".to_string(),
+            "ETF" => "ETFs".to_string(),
+            "CRYPTOCURRENCY" => "Crypto".to_string(),
+            "INDEX" => "Indices".to_string(),
+            "MUTUALFUND" => "Funds".to_string(),
+            "CURRENCY" => "Currencies".to_string(),
+            "FUTURE" => "Futures".to_string(),
+            other => other.to_string(),
+        },
+        "exchange" => stock.exchange(),
+        _ => String::new(),
+    };
+    (!key.is_empty()).then_some(key)
+}
+
+fn section_stocks(header: &ListHeader, sorted_stocks: &SortListModel) -> Vec<StockObject> {
+    (header.start()..header.end())
+        .filter_map(|i| sorted_stocks.item(i).and_downcast::<StockObject>())
+        .collect()
+}
+
+/// Average daily change of `stocks`, and the value of the holdings if there are any
+fn section_subtotal(stocks: &[StockObject]) -> String {
+    if stocks.is_empty() {
+        return String::new();
+    }
+    let average = stocks.iter().map(|s| s.pct_change_1d()).sum::<f64>() / stocks.len() as f64;
+    let sign = if average >= 0.0 { "+" } else { "" };
+    let mut subtotal = format!("avg {}{:.2}%", sign, average * 100.0);
+
+    if stocks.iter().any(|s| s.quantity() > 0.0) {
+        let value: f64 = stocks.iter().map(|s| s.value()).sum();
+        subtotal.push_str(&format!("  ·  value {:.2}", value));
+    }
+    subtotal
+}
+
+/// Moves the stock with `ticker` next to `target` in the store, returns whether it moved
+fn move_stock(stocks: &ListStore, ticker: &str, target: &StockObject, after: bool) -> bool {
+    if target.ticker() == ticker {
+        return false;
+    }
+    let Some((from, stock)) = (0..stocks.n_items())
+        .filter_map(|i| Some((i, stocks.item(i).and_downcast::<StockObject>()?)))
+        .find(|(_, stock)| stock.ticker() == ticker)
+    else {
+        return false;
+    };
+
+    stocks.remove(from);
+    let to = stocks.find(target).map_or(from, |to| to + after as u32);
+    stocks.insert(to, &stock);
+    to != from
+}
+
+fn contains_ticker(stocks: &ListStore, ticker: &str) -> bool {
+    (0..stocks.n_items()).any(|i| {
+        stocks
+            .item(i)
+            .and_downcast::<StockObject>()
+            .is_some_and(|s| s.ticker() == ticker)
+    })
+}
+
+/// Saves the watchlist, reporting failures in the log and as a toast
+fn save_store(stocks: &ListStore, toast: &Toast) {
+    if let Err(e) = save_tickers(&store_entries(stocks)) {
+        eprintln!("Failed to save watchlist: {}", e);
+        toast.show(&format!("Failed to save watchlist: {}", e));
     }
 }
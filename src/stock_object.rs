@@ -1,5 +1,16 @@
+use crate::persistence::TickerEntry;
 use gtk::{glib, prelude::*, subclass::prelude::*};
 
+/// A series of closing prices, e.g. the intraday prices of a ticker
+#[derive(Debug, Clone, Default, PartialEq, glib::Boxed)]
+#[boxed_type(name = "PriceSeries")]
+pub struct PriceSeries {
+    pub timestamps: Vec<i64>,
+    pub closes: Vec<f64>,
+    /// Close of the previous period, the baseline the series is compared against
+    pub prev_close: f64,
+}
+
 mod imp {
     use super::*;
     use glib::Properties;
@@ -18,6 +29,27 @@ mod imp {
         pub pct_change_1w: RefCell<f64>,
         #[property(get, set)]
         pub pct_change_1d: RefCell<f64>,
+        #[property(get, set)]
+        pub intraday: RefCell<PriceSeries>,
+        #[property(get, set)]
+        pub volume: RefCell<f64>,
+        #[property(get, set)]
+        pub market_cap: RefCell<f64>,
+        /// Yahoo's quote type, e.g. "EQUITY" or "ETF", empty until the first update
+        #[property(get, set)]
+        pub quote_type: RefCell<String>,
+        #[property(get, set)]
+        pub exchange: RefCell<String>,
+        #[property(get, set)]
+        pub quantity: RefCell<f64>,
+        #[property(get, set)]
+        pub cost: RefCell<f64>,
+        #[property(get, set)]
+        pub tags: RefCell<Vec<String>>,
+        #[property(get, set)]
+        pub display_name: RefCell<String>,
+        #[property(get, set)]
+        pub notes: RefCell<String>,
     }
 
     #[glib::object_subclass]
@@ -44,4 +76,47 @@ impl StockObject {
             .property("pct_change_1d", 0.0)
             .build()
     }
+
+    pub fn from_entry(entry: &TickerEntry) -> Self {
+        let stock = Self::new(&entry.ticker, &entry.name);
+        stock.apply_entry(entry);
+        stock
+    }
+
+    /// Copies the user editable fields of `entry` onto this object
+    pub fn apply_entry(&self, entry: &TickerEntry) {
+        self.set_name(entry.name.as_str());
+        self.set_quantity(entry.quantity);
+        self.set_cost(entry.cost);
+        self.set_tags(entry.tags.clone());
+        self.set_display_name(entry.display_name.as_str());
+        self.set_notes(entry.notes.as_str());
+    }
+
+    /// The custom display name if one is set, otherwise the name from the search
+    pub fn title(&self) -> String {
+        let display_name = self.display_name();
+        if display_name.is_empty() {
+            self.name()
+        } else {
+            display_name
+        }
+    }
+
+    /// Market value of the units held
+    pub fn value(&self) -> f64 {
+        self.quantity() * self.price()
+    }
+
+    pub fn to_entry(&self) -> TickerEntry {
+        TickerEntry {
+            ticker: self.ticker(),
+            name: self.name(),
+            quantity: self.quantity(),
+            cost: self.cost(),
+            tags: self.tags(),
+            display_name: self.display_name(),
+            notes: self.notes(),
+        }
+    }
 }
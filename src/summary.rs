@@ -0,0 +1,120 @@
+use crate::stock_object::StockObject;
+
+/// Totals over the watchlist. Money amounts only include holdings, the advancers and
+/// decliners count every stock that has a price.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PortfolioSummary {
+    /// Number of stocks with a quantity
+    pub holdings: usize,
+    /// Market value of all holdings
+    pub value: f64,
+    /// Change of `value` since the previous close, in money and as a fraction
+    pub day_change: f64,
+    pub day_pct: f64,
+    /// Value minus cost of the holdings that have a cost, in money and as a fraction
+    pub unrealized: f64,
+    pub unrealized_pct: f64,
+    pub advancers: usize,
+    pub decliners: usize,
+}
+
+impl PortfolioSummary {
+    pub fn compute(stocks: &[StockObject]) -> Self {
+        let mut summary = Self::default();
+        let mut cost_basis = 0.0;
+
+        for stock in stocks.iter().filter(|s| s.price() > 0.0) {
+            let change = stock.pct_change_1d();
+            if change > 0.0 {
+                summary.advancers += 1;
+            } else if change < 0.0 {
+                summary.decliners += 1;
+            }
+
+            if stock.quantity() <= 0.0 {
+                continue;
+            }
+            let value = stock.value();
+            summary.holdings += 1;
+            summary.value += value;
+            // The change is relative to the previous close, so that's where the value was
+            summary.day_change += value - value / (1.0 + change);
+            if stock.cost() > 0.0 {
+                let cost = stock.quantity() * stock.cost();
+                cost_basis += cost;
+                summary.unrealized += value - cost;
+            }
+        }
+
+        let previous_value = summary.value - summary.day_change;
+        if previous_value > 0.0 {
+            summary.day_pct = summary.day_change / previous_value;
+        }
+        if cost_basis > 0.0 {
+            summary.unrealized_pct = summary.unrealized / cost_basis;
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(price: f64, change: f64, quantity: f64, cost: f64) -> StockObject {
+        let stock = StockObject::new("TEST", "Test");
+        stock.set_price(price);
+        stock.set_pct_change_1d(change);
+        stock.set_quantity(quantity);
+        stock.set_cost(cost);
+        stock
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn empty_watchlist() {
+        assert_eq!(PortfolioSummary::compute(&[]), PortfolioSummary::default());
+    }
+
+    #[test]
+    fn totals_holdings() {
+        let summary = PortfolioSummary::compute(&[
+            // Up 10% today, bought at 50
+            stock(110.0, 0.1, 10.0, 50.0),
+            // Unchanged, without a cost
+            stock(50.0, 0.0, 2.0, 0.0),
+        ]);
+
+        assert_eq!(summary.holdings, 2);
+        assert_close(summary.value, 1200.0);
+        assert_close(summary.day_change, 100.0);
+        assert_close(summary.day_pct, 100.0 / 1100.0);
+        assert_close(summary.unrealized, 600.0);
+        assert_close(summary.unrealized_pct, 600.0 / 500.0);
+    }
+
+    #[test]
+    fn counts_advancers_and_decliners() {
+        let summary = PortfolioSummary::compute(&[
+            stock(110.0, 0.1, 10.0, 50.0),
+            // Watched only
+            stock(20.0, -0.05, 0.0, 0.0),
+            stock(30.0, 0.0, 0.0, 0.0),
+            // No price yet
+            stock(0.0, 0.5, 5.0, 10.0),
+        ]);
+
+        assert_eq!(summary.advancers, 1);
+        assert_eq!(summary.decliners, 1);
+        assert_eq!(summary.holdings, 1);
+        assert_close(summary.value, 1100.0);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{persistence::get_config_dir, settings::AppSettings};
+use gtk::{
+    CssProvider, STYLE_PROVIDER_PRIORITY_APPLICATION, STYLE_PROVIDER_PRIORITY_USER,
+    gdk::Display,
+    gio::{self, FileMonitor, FileMonitorFlags},
+    glib,
+    prelude::*,
+    style_context_add_provider_for_display,
+};
+use std::path::PathBuf;
+
+/// Ids and titles of the built-in theme variants, the empty id is the default look
+pub const THEMES: [(&str, &str); 3] = [
+    ("", "Default"),
+    ("high-contrast", "High Contrast"),
+    ("colorblind", "Colour-blind Safe"),
+];
+
+/// Loads the embedded stylesheet
+pub fn load_base_style(display: &Display) {
+    let css_provider = CssProvider::new();
+    css_provider.load_from_string(include_str!("../resources/style.css"));
+    style_context_add_provider_for_display(
+        display,
+        &css_provider,
+        STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
+}
+
+/// Applies the theme variant picked in the settings on top of the embedded stylesheet,
+/// and `style.css` from the config directory on top of both. The variant follows the
+/// settings and the user stylesheet is reloaded whenever it changes on disk.
+pub fn apply(display: &Display, settings: &AppSettings) {
+    let variant_provider = CssProvider::new();
+    style_context_add_provider_for_display(
+        display,
+        &variant_provider,
+        STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
+    );
+    load_variant(&variant_provider, &settings.theme());
+
+    let user_provider = CssProvider::new();
+    user_provider.connect_parsing_error(|_, section, error| {
+        eprintln!("{}: {}", section, error);
+    });
+    style_context_add_provider_for_display(display, &user_provider, STYLE_PROVIDER_PRIORITY_USER);
+    load_user_style(&user_provider);
+
+    let monitor = match gio::File::for_path(user_style_path())
+        .monitor_file(FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)
+    {
+        Ok(monitor) => {
+            monitor.connect_changed(glib::clone!(
+                #[weak]
+                user_provider,
+                move |_, _, _, _| load_user_style(&user_provider)
+            ));
+            Some(monitor)
+        }
+        Err(e) => {
+            eprintln!("Could not watch {}: {}", user_style_path().display(), e);
+            None
+        }
+    };
+
+    settings.connect_notify_local(Some("theme"), move |settings, _| {
+        // Keeps the monitor alive for as long as the settings
+        let _: &Option<FileMonitor> = &monitor;
+        load_variant(&variant_provider, &settings.theme());
+    });
+}
+
+fn load_variant(provider: &CssProvider, theme: &str) {
+    provider.load_from_string(match theme {
+        "high-contrast" => include_str!("../resources/themes/high-contrast.css"),
+        "colorblind" => include_str!("../resources/themes/colorblind.css"),
+        _ => "",
+    });
+}
+
+/// A missing file clears the provider, so deleting it goes back to the built-in look
+fn load_user_style(provider: &CssProvider) {
+    let path = user_style_path();
+    if path.exists() {
+        provider.load_from_path(&path);
+    } else {
+        provider.load_from_string("");
+    }
+}
+
+fn user_style_path() -> PathBuf {
+    get_config_dir().join("style.css")
+}
@@ -0,0 +1,240 @@
+use crate::{stock_manager::StockManager, stock_object::StockObject};
+use gtk::{
+    Application, ApplicationWindow, Box, EventControllerMotion, GestureClick, Label, ListItem,
+    ListView, NoSelection, Orientation, PolicyType, PopoverMenu, ScrolledWindow,
+    SignalListItemFactory, Window,
+    gdk::{self, Rectangle},
+    gio::{Menu, SimpleAction},
+    glib::{self, Binding},
+    prelude::*,
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+/// Scroll speed of the strip in pixels per second
+const SCROLL_SPEED: f64 = 40.0;
+
+/// Key of the property bindings kept on each chip, so unbind can drop them
+const CHIP_BINDINGS: &str = "chip-bindings";
+
+/// Registers the `app.ticker-tape` toggle, which swaps the main window for a narrow
+/// borderless strip of the watchlist and back
+pub fn add_actions(
+    application: &Application,
+    window: &ApplicationWindow,
+    manager: Rc<StockManager>,
+) {
+    let tape: Rc<RefCell<Option<Window>>> = Rc::default();
+
+    let tape_action = SimpleAction::new_stateful("ticker-tape", None, &false.to_variant());
+    tape_action.connect_change_state(glib::clone!(
+        #[weak]
+        application,
+        #[weak]
+        window,
+        move |action, state| {
+            let Some(show_tape) = state.and_then(|s| s.get::<bool>()) else {
+                return;
+            };
+            action.set_state(&show_tape.into());
+
+            // The strip is kept around once created, like the main window it's only hidden
+            if show_tape {
+                let tape_window = tape
+                    .borrow_mut()
+                    .get_or_insert_with(|| create_tape_window(&application, action, &manager))
+                    .clone();
+                window.set_visible(false);
+                tape_window.present();
+            } else {
+                if let Some(tape_window) = tape.borrow().as_ref() {
+                    tape_window.set_visible(false);
+                }
+                window.present();
+            }
+        }
+    ));
+    application.add_action(&tape_action);
+}
+
+fn create_tape_window(
+    application: &Application,
+    action: &SimpleAction,
+    manager: &StockManager,
+) -> Window {
+    let factory = SignalListItemFactory::new();
+    factory.connect_setup(|_, list_item| {
+        let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+        let chip = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+        chip.append(&Label::builder().css_classes(["heading"]).build());
+        chip.append(&Label::builder().css_classes(["numeric"]).build());
+        chip.append(&Label::builder().css_classes(["numeric"]).build());
+        list_item.set_child(Some(&chip));
+    });
+    factory.connect_bind(|_, list_item| {
+        let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+        let stock = list_item.item().and_downcast::<StockObject>().unwrap();
+        let chip = list_item.child().and_downcast::<Box>().unwrap();
+        let ticker = chip.first_child().and_downcast::<Label>().unwrap();
+        let price = ticker.next_sibling().and_downcast::<Label>().unwrap();
+        let change = price.next_sibling().and_downcast::<Label>().unwrap();
+
+        ticker.set_text(&stock.ticker());
+        let bindings = vec![
+            stock
+                .bind_property("price", &price, "label")
+                .transform_to(|_, value: f64| Some(format!("{:.2}", value)))
+                .sync_create()
+                .build(),
+            stock
+                .bind_property("pct-change-1d", &change, "label")
+                .transform_to(|_, value: f64| {
+                    let sign = if value >= 0.0 { "+" } else { "" };
+                    Some(format!("{}{:.2}%", sign, value * 100.0))
+                })
+                .sync_create()
+                .build(),
+            stock
+                .bind_property("pct-change-1d", &change, "css-classes")
+                .transform_to(|_, value: f64| {
+                    let direction = if value >= 0.0 { "success" } else { "error" };
+                    Some(vec![direction.to_string(), "numeric".to_string()])
+                })
+                .sync_create()
+                .build(),
+        ];
+        // SAFETY: Only ever stored and taken as this type, see the unbind below
+        unsafe { chip.set_data(CHIP_BINDINGS, bindings) };
+    });
+    factory.connect_unbind(|_, list_item| {
+        let list_item = list_item.downcast_ref::<ListItem>().unwrap();
+        let chip = list_item.child().and_downcast::<Box>().unwrap();
+        // SAFETY: Set in the bind above
+        if let Some(bindings) = unsafe { chip.steal_data::<Vec<Binding>>(CHIP_BINDINGS) } {
+            for binding in bindings {
+                binding.unbind();
+            }
+        }
+    });
+
+    let strip = ListView::builder()
+        .model(&NoSelection::new(Some(manager.sorted_stocks())))
+        .factory(&factory)
+        .orientation(Orientation::Horizontal)
+        .build();
+    let scrolled = ScrolledWindow::builder()
+        .child(&strip)
+        .hscrollbar_policy(PolicyType::External)
+        .vscrollbar_policy(PolicyType::Never)
+        .build();
+
+    let tape_window = Window::builder()
+        .application(application)
+        .title("Stockfin")
+        .decorated(false)
+        .default_width(720)
+        .child(&scrolled)
+        .build();
+
+    setup_scrolling(&scrolled);
+
+    // Without decorations the strip itself is the handle to move the window
+    let drag = GestureClick::new();
+    drag.connect_pressed(glib::clone!(
+        #[weak]
+        tape_window,
+        move |gesture, _, x, y| {
+            let toplevel = tape_window.surface().and_downcast::<gdk::Toplevel>();
+            if let (Some(toplevel), Some(device)) = (toplevel, gesture.device()) {
+                toplevel.begin_move(
+                    &device,
+                    gesture.current_button() as i32,
+                    x,
+                    y,
+                    gesture.current_event_time(),
+                );
+            }
+        }
+    ));
+    scrolled.add_controller(drag);
+
+    let menu = Menu::new();
+    // Unchecking it goes back to the full window
+    menu.append(Some("Ticker Tape"), Some("app.ticker-tape"));
+    menu.append(Some("Refresh"), Some("app.refresh"));
+    let popover = PopoverMenu::from_model(Some(&menu));
+    popover.set_parent(&scrolled);
+    popover.set_has_arrow(false);
+
+    let right_click = GestureClick::new();
+    right_click.set_button(3);
+    right_click.connect_pressed(glib::clone!(
+        #[weak]
+        popover,
+        move |_, _, x, y| {
+            popover.set_pointing_to(Some(&Rectangle::new(x as i32, y as i32, 0, 0)));
+            popover.popup();
+        }
+    ));
+    scrolled.add_controller(right_click);
+
+    // Closing the strip from the window manager goes back to the full window
+    tape_window.connect_close_request(glib::clone!(
+        #[weak]
+        action,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |_| {
+            action.change_state(&false.to_variant());
+            glib::Propagation::Stop
+        }
+    ));
+
+    tape_window
+}
+
+/// Moves the strip along at `SCROLL_SPEED`, starting over at the end. It holds still while
+/// the pointer is over it, so a chip can be read or the strip scrolled by hand.
+fn setup_scrolling(scrolled: &ScrolledWindow) {
+    let paused = Rc::new(Cell::new(false));
+    let motion = EventControllerMotion::new();
+    motion.connect_enter(glib::clone!(
+        #[strong]
+        paused,
+        move |_, _, _| paused.set(true)
+    ));
+    motion.connect_leave(glib::clone!(
+        #[strong]
+        paused,
+        move |_| paused.set(false)
+    ));
+    scrolled.add_controller(motion);
+
+    // Frame time of the previous tick, in microseconds
+    let last_frame = Cell::new(None::<i64>);
+    scrolled.add_tick_callback(move |scrolled, clock| {
+        let now = clock.frame_time();
+        let elapsed = last_frame
+            .replace(Some(now))
+            .map_or(0.0, |last| (now - last) as f64 / 1e6);
+        if paused.get() {
+            return glib::ControlFlow::Continue;
+        }
+
+        let adjustment = scrolled.hadjustment();
+        let end = adjustment.upper() - adjustment.page_size();
+        if end <= 0.0 {
+            return glib::ControlFlow::Continue;
+        }
+        let value = adjustment.value() + SCROLL_SPEED * elapsed;
+        adjustment.set_value(if value >= end { 0.0 } else { value });
+        glib::ControlFlow::Continue
+    });
+}
@@ -0,0 +1,158 @@
+use gtk::{
+    Align, Box, Button, Label, Orientation, Revealer, RevealerTransitionType,
+    glib::{self, SourceId, timeout_add_local_once},
+    prelude::*,
+};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+/// How long a toast stays visible unless dismissed
+const TOAST_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Callback = std::boxed::Box<dyn FnOnce()>;
+
+/// A small in-window notification, meant to be placed in an `Overlay` above the content
+#[derive(Clone)]
+pub struct Toast {
+    revealer: Revealer,
+    label: Label,
+    action_button: Button,
+    hide_source: Rc<RefCell<Option<SourceId>>>,
+    /// Run when the action button is clicked
+    on_action: Rc<RefCell<Option<Callback>>>,
+    /// Run when a toast with an action goes away without it being clicked
+    on_dismiss: Rc<RefCell<Option<Callback>>>,
+}
+
+impl Default for Toast {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Toast {
+    pub fn new() -> Self {
+        let label = Label::builder().wrap(true).xalign(0.0).build();
+        let action_button = Button::builder()
+            .css_classes(["flat"])
+            .valign(Align::Center)
+            .visible(false)
+            .build();
+        let close_button = Button::builder()
+            .icon_name("window-close-symbolic")
+            .css_classes(["flat", "circular"])
+            .valign(Align::Center)
+            .build();
+
+        let content = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(12)
+            .css_classes(["toast"])
+            .build();
+        content.append(&label);
+        content.append(&action_button);
+        content.append(&close_button);
+
+        let revealer = Revealer::builder()
+            .child(&content)
+            .halign(Align::Center)
+            .valign(Align::End)
+            .margin_bottom(12)
+            .transition_type(RevealerTransitionType::SlideUp)
+            .build();
+
+        let toast = Self {
+            revealer,
+            label,
+            action_button,
+            hide_source: Rc::new(RefCell::new(None)),
+            on_action: Rc::new(RefCell::new(None)),
+            on_dismiss: Rc::new(RefCell::new(None)),
+        };
+
+        toast.action_button.connect_clicked(glib::clone!(
+            #[strong]
+            toast,
+            move |_| {
+                toast.on_dismiss.take();
+                let on_action = toast.on_action.take();
+                toast.hide();
+                if let Some(on_action) = on_action {
+                    on_action();
+                }
+            }
+        ));
+        close_button.connect_clicked(glib::clone!(
+            #[strong]
+            toast,
+            move |_| toast.hide()
+        ));
+
+        toast
+    }
+
+    pub fn widget(&self) -> &Revealer {
+        &self.revealer
+    }
+
+    /// Shows `message`, replacing whatever toast is currently visible
+    pub fn show(&self, message: &str) {
+        self.finish();
+        self.action_button.set_visible(false);
+        self.reveal(message);
+    }
+
+    /// Shows `message` with a button, e.g. "Undo". `on_action` runs if the button is
+    /// clicked, otherwise `on_dismiss` runs once the toast goes away, including when it
+    /// is replaced by another toast.
+    pub fn show_with_action(
+        &self,
+        message: &str,
+        action_label: &str,
+        on_action: impl FnOnce() + 'static,
+        on_dismiss: impl FnOnce() + 'static,
+    ) {
+        self.finish();
+        self.on_action
+            .replace(Some(std::boxed::Box::new(on_action)));
+        self.on_dismiss
+            .replace(Some(std::boxed::Box::new(on_dismiss)));
+        self.action_button.set_label(action_label);
+        self.action_button.set_visible(true);
+        self.reveal(message);
+    }
+
+    pub fn hide(&self) {
+        self.cancel_timeout();
+        self.revealer.set_reveal_child(false);
+        self.finish();
+    }
+
+    fn reveal(&self, message: &str) {
+        self.cancel_timeout();
+        self.label.set_text(message);
+        self.revealer.set_reveal_child(true);
+
+        let toast = self.clone();
+        let source = timeout_add_local_once(TOAST_TIMEOUT, move || {
+            toast.hide_source.borrow_mut().take();
+            toast.hide();
+        });
+        self.hide_source.replace(Some(source));
+    }
+
+    /// Runs the dismiss callback of the current toast, if it has one
+    fn finish(&self) {
+        self.on_action.take();
+        // Taken before running, the callback may show another toast
+        let on_dismiss = self.on_dismiss.take();
+        if let Some(on_dismiss) = on_dismiss {
+            on_dismiss();
+        }
+    }
+
+    fn cancel_timeout(&self) {
+        if let Some(source) = self.hide_source.borrow_mut().take() {
+            source.remove();
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{persistence::TickerEntry, stock_api::SymbolStatus, stock_manager::StockManager};
+use gtk::{
+    Align, Application, ApplicationWindow, Box, Button, Label, ListBox, Orientation,
+    ScrolledWindow, SelectionMode, Window,
+    gio::SimpleAction,
+    glib::{self, WeakRef},
+    prelude::*,
+};
+use std::{rc::Rc, time::Duration};
+
+/// Registers the `app.validate-watchlist` action
+pub fn add_actions(
+    application: &Application,
+    window: &ApplicationWindow,
+    manager: Rc<StockManager>,
+) {
+    let validate_action = SimpleAction::new("validate-watchlist", None);
+    validate_action.connect_activate(glib::clone!(
+        #[weak]
+        window,
+        #[strong]
+        manager,
+        move |_, _| show_validation_window(&window, manager.clone())
+    ));
+    application.add_action(&validate_action);
+}
+
+/// Checks every saved symbol for prices and lists the ones that have none, with a
+/// replacement when one was found. Nothing changes until a row's button is clicked.
+/// Symbols that couldn't be checked are only counted, they may well be fine.
+fn show_validation_window(parent: &ApplicationWindow, manager: Rc<StockManager>) {
+    let entries = manager.entries();
+
+    let status = Label::builder()
+        .label(format!("Checking 0 of {}…", entries.len()))
+        .halign(Align::Start)
+        .wrap(true)
+        .build();
+    let list = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+
+    let layout = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    layout.append(&status);
+    layout.append(
+        &ScrolledWindow::builder()
+            .child(&list)
+            .vexpand(true)
+            .min_content_height(160)
+            .build(),
+    );
+
+    let validation_window = Window::builder()
+        .title("Validate Watchlist")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(420)
+        .default_height(360)
+        .child(&layout)
+        .build();
+    validation_window.present();
+
+    let window_ref = validation_window.downgrade();
+    glib::MainContext::default().spawn_local(async move {
+        let api = manager.api();
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let mut problems = 0;
+        let mut unchecked = 0;
+        for (i, entry) in entries.iter().enumerate() {
+            // Stop checking once the window is closed
+            if window_ref.upgrade().is_none() {
+                return;
+            }
+
+            match rt.block_on(api.validate_symbol(&entry.ticker, &entry.name)) {
+                SymbolStatus::Valid => {}
+                SymbolStatus::Unknown => unchecked += 1,
+                symbol_status => {
+                    problems += 1;
+                    list.append(&problem_row(entry, symbol_status, &manager, &window_ref));
+                }
+            }
+            status.set_text(&format!("Checking {} of {}…", i + 1, entries.len()));
+
+            // Let the main loop draw and handle input between tickers
+            glib::timeout_future(Duration::ZERO).await;
+        }
+
+        let mut summary = match (problems, unchecked) {
+            (0, 0) => "Every symbol returned prices.".to_string(),
+            (0, _) => "No symbol is known to be missing prices.".to_string(),
+            (1, _) => "1 symbol returned no prices.".to_string(),
+            (n, _) => format!("{} symbols returned no prices.", n),
+        };
+        match unchecked {
+            0 => {}
+            1 => summary.push_str(" 1 couldn't be checked, try again once Yahoo is reachable."),
+            n => summary.push_str(&format!(
+                " {} couldn't be checked, try again once Yahoo is reachable.",
+                n
+            )),
+        }
+        status.set_text(&summary);
+    });
+}
+
+fn problem_row(
+    entry: &TickerEntry,
+    symbol_status: SymbolStatus,
+    manager: &Rc<StockManager>,
+    validation_window: &WeakRef<Window>,
+) -> Box {
+    let row_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(6)
+        .margin_end(6)
+        .build();
+
+    let detail = match &symbol_status {
+        SymbolStatus::Renamed { symbol, name } => format!("Possibly now {} ({})", symbol, name),
+        _ => "No prices, possibly delisted".to_string(),
+    };
+    let text = Box::builder()
+        .orientation(Orientation::Vertical)
+        .hexpand(true)
+        .build();
+    text.append(&Label::builder().label(&entry.ticker).xalign(0.0).build());
+    text.append(
+        &Label::builder()
+            .label(&detail)
+            .xalign(0.0)
+            .wrap(true)
+            .css_classes(["dim-label"])
+            .build(),
+    );
+    row_box.append(&text);
+
+    let buttons = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(6)
+        .valign(Align::Center)
+        .build();
+    if let SymbolStatus::Renamed { symbol, name } = symbol_status {
+        let replace_button = Button::builder()
+            .label(format!("Use {}", symbol))
+            .css_classes(["suggested-action"])
+            .build();
+        let ticker = entry.ticker.clone();
+        replace_button.connect_clicked(glib::clone!(
+            #[strong]
+            manager,
+            #[weak]
+            buttons,
+            move |_| {
+                manager.replace_ticker(&ticker, &symbol, &name);
+                manager
+                    .toast()
+                    .show(&format!("Replaced {} with {}", ticker, symbol));
+                buttons.set_sensitive(false);
+            }
+        ));
+        buttons.append(&replace_button);
+    }
+
+    let remove_button = Button::with_label("Remove");
+    let ticker = entry.ticker.clone();
+    remove_button.connect_clicked(glib::clone!(
+        #[strong]
+        manager,
+        #[weak]
+        buttons,
+        move |_| {
+            manager.remove_ticker(&ticker);
+            manager.toast().show(&format!("Removed {}", ticker));
+            buttons.set_sensitive(false);
+        }
+    ));
+    buttons.append(&remove_button);
+
+    // Shows the row in the main window
+    let show_button = Button::builder()
+        .icon_name("go-jump-symbolic")
+        .tooltip_text("Show in list")
+        .build();
+    let ticker = entry.ticker.clone();
+    show_button.connect_clicked(glib::clone!(
+        #[strong]
+        manager,
+        #[strong]
+        validation_window,
+        move |_| {
+            if manager.focus_stock(&ticker)
+                && let Some(window) = validation_window.upgrade()
+            {
+                window.close();
+            }
+        }
+    ));
+    buttons.append(&show_button);
+
+    row_box.append(&buttons);
+    row_box
+}